@@ -1,10 +1,12 @@
 CREATE TABLE IF NOT EXISTS logs (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
     game_id INTEGER NOT NULL,
-    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
-    updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
-    start_date TEXT DEFAULT CURRENT_TIMESTAMP,
-    end_date TEXT DEFAULT CURRENT_TIMESTAMP,
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    start_date TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    start_date_offset INTEGER DEFAULT 0,
+    end_date TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    end_date_offset INTEGER DEFAULT 0,
     rating INTEGER DEFAULT 0,
     notes TEXT,
     status TEXT,
@@ -17,8 +19,8 @@ CREATE TABLE IF NOT EXISTS logs (
 
 CREATE TABLE IF NOT EXISTS user_settings (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
-    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
-    updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
     username TEXT,
     executable_paths TEXT,
     process_monitoring_enabled INTEGER DEFAULT 0,
@@ -28,9 +30,63 @@ CREATE TABLE IF NOT EXISTS user_settings (
 
 CREATE TABLE IF NOT EXISTS executable_details (
     id INTEGER PRIMARY KEY AUTOINCREMENT,
-    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
-    updated_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
     executable_name TEXT,
     game_id INTEGER NOT NULL,
     CONSTRAINT unique_executable_name UNIQUE (executable_name)
 );
+
+CREATE TABLE IF NOT EXISTS attachments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    log_id INTEGER NOT NULL,
+    file_name TEXT NOT NULL,
+    stored_name TEXT NOT NULL,
+    thumbnail_name TEXT,
+    size_bytes INTEGER NOT NULL,
+    FOREIGN KEY (log_id) REFERENCES logs(id) ON DELETE CASCADE
+);
+
+CREATE TABLE IF NOT EXISTS journal_entries (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    log_id INTEGER NOT NULL,
+    entry_date TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    entry_date_offset INTEGER DEFAULT 0,
+    content TEXT NOT NULL,
+    is_spoiler INTEGER DEFAULT 0,
+    session_log_id INTEGER,
+    FOREIGN KEY (log_id) REFERENCES logs(id) ON DELETE CASCADE,
+    FOREIGN KEY (session_log_id) REFERENCES logs(id) ON DELETE SET NULL,
+    CONSTRAINT boolean_is_spoiler CHECK (is_spoiler IN (0,1)),
+    CONSTRAINT valid_entry_date CHECK (date(entry_date) IS NOT NULL)
+);
+
+CREATE TABLE IF NOT EXISTS reviews (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    updated_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    log_id INTEGER NOT NULL,
+    verdict TEXT,
+    would_replay INTEGER DEFAULT 0,
+    FOREIGN KEY (log_id) REFERENCES logs(id) ON DELETE CASCADE,
+    CONSTRAINT unique_log_id UNIQUE (log_id),
+    CONSTRAINT boolean_would_replay CHECK (would_replay IN (0,1))
+);
+
+CREATE TABLE IF NOT EXISTS review_scores (
+    review_id INTEGER NOT NULL,
+    dimension TEXT NOT NULL,
+    score INTEGER NOT NULL,
+    FOREIGN KEY (review_id) REFERENCES reviews(id) ON DELETE CASCADE,
+    PRIMARY KEY (review_id, dimension),
+    CONSTRAINT valid_score CHECK (score >= 0 AND score <= 5)
+);
+
+CREATE TABLE IF NOT EXISTS picks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    created_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now')),
+    game_id INTEGER NOT NULL
+);
@@ -5,6 +5,8 @@ CREATE TABLE IF NOT EXISTS games (
     category INTEGER,
     version_parent INTEGER,
     total_rating REAL,
+    summary TEXT,
+    storyline TEXT,
     FOREIGN KEY (cover_id) REFERENCES covers(id) ON DELETE SET NULL
 );
 
@@ -14,6 +16,13 @@ CREATE VIRTUAL TABLE IF NOT EXISTS games_fts USING fts5(
     name
 );
 
+CREATE VIRTUAL TABLE IF NOT EXISTS games_trigram USING fts5(
+    content_rowid="id",
+    content="games",
+    name,
+    tokenize="trigram"
+);
+
 CREATE TABLE IF NOT EXISTS covers (
     id INTEGER PRIMARY KEY,
     image_id TEXT
@@ -46,6 +55,8 @@ CREATE TABLE IF NOT EXISTS similar_games (
     PRIMARY KEY (game_id, similar_game_id)
 );
 
+CREATE INDEX IF NOT EXISTS similar_games_similar_game_id ON similar_games (similar_game_id);
+
 CREATE TABLE IF NOT EXISTS game_platforms (
     game_id INTEGER,
     platform_id INTEGER,
@@ -61,3 +72,148 @@ CREATE TABLE IF NOT EXISTS popularity_primitives (
     value REAL,
     FOREIGN KEY (game_id) REFERENCES games(id)
 );
+
+CREATE INDEX IF NOT EXISTS popularity_primitives_game_id ON popularity_primitives (game_id);
+
+CREATE TABLE IF NOT EXISTS game_time_to_beats (
+    id INTEGER PRIMARY KEY,
+    game_id INTEGER,
+    hastily INTEGER,
+    normally INTEGER,
+    completely INTEGER,
+    FOREIGN KEY (game_id) REFERENCES games(id)
+);
+
+CREATE INDEX IF NOT EXISTS game_time_to_beats_game_id ON game_time_to_beats (game_id);
+
+CREATE TABLE IF NOT EXISTS genres (
+    id INTEGER PRIMARY KEY,
+    name TEXT
+);
+
+CREATE TABLE IF NOT EXISTS game_genres (
+    game_id INTEGER,
+    genre_id INTEGER,
+    FOREIGN KEY (game_id) REFERENCES games(id),
+    FOREIGN KEY (genre_id) REFERENCES genres(id),
+    PRIMARY KEY (game_id, genre_id)
+);
+
+CREATE TABLE IF NOT EXISTS themes (
+    id INTEGER PRIMARY KEY,
+    name TEXT
+);
+
+CREATE TABLE IF NOT EXISTS game_themes (
+    game_id INTEGER,
+    theme_id INTEGER,
+    FOREIGN KEY (game_id) REFERENCES games(id),
+    FOREIGN KEY (theme_id) REFERENCES themes(id),
+    PRIMARY KEY (game_id, theme_id)
+);
+
+CREATE TABLE IF NOT EXISTS game_modes (
+    id INTEGER PRIMARY KEY,
+    name TEXT
+);
+
+CREATE TABLE IF NOT EXISTS game_game_modes (
+    game_id INTEGER,
+    game_mode_id INTEGER,
+    FOREIGN KEY (game_id) REFERENCES games(id),
+    FOREIGN KEY (game_mode_id) REFERENCES game_modes(id),
+    PRIMARY KEY (game_id, game_mode_id)
+);
+
+CREATE TABLE IF NOT EXISTS franchises (
+    id INTEGER PRIMARY KEY,
+    name TEXT
+);
+
+CREATE TABLE IF NOT EXISTS game_franchises (
+    game_id INTEGER,
+    franchise_id INTEGER,
+    FOREIGN KEY (game_id) REFERENCES games(id),
+    FOREIGN KEY (franchise_id) REFERENCES franchises(id),
+    PRIMARY KEY (game_id, franchise_id)
+);
+
+CREATE TABLE IF NOT EXISTS collections (
+    id INTEGER PRIMARY KEY,
+    name TEXT
+);
+
+CREATE TABLE IF NOT EXISTS game_collections (
+    game_id INTEGER,
+    collection_id INTEGER,
+    FOREIGN KEY (game_id) REFERENCES games(id),
+    FOREIGN KEY (collection_id) REFERENCES collections(id),
+    PRIMARY KEY (game_id, collection_id)
+);
+
+CREATE TABLE IF NOT EXISTS companies (
+    id INTEGER PRIMARY KEY,
+    name TEXT
+);
+
+CREATE TABLE IF NOT EXISTS involved_companies (
+    id INTEGER PRIMARY KEY,
+    game_id INTEGER,
+    company_id INTEGER,
+    developer INTEGER DEFAULT 0,
+    publisher INTEGER DEFAULT 0,
+    FOREIGN KEY (game_id) REFERENCES games(id),
+    FOREIGN KEY (company_id) REFERENCES companies(id)
+);
+
+CREATE INDEX IF NOT EXISTS involved_companies_game_id ON involved_companies (game_id);
+
+CREATE TABLE IF NOT EXISTS release_dates (
+    id INTEGER PRIMARY KEY,
+    game_id INTEGER,
+    platform_id INTEGER,
+    date INTEGER,
+    region INTEGER,
+    FOREIGN KEY (game_id) REFERENCES games(id)
+);
+
+CREATE INDEX IF NOT EXISTS release_dates_game_id ON release_dates (game_id);
+
+CREATE TABLE IF NOT EXISTS alternative_names (
+    id INTEGER PRIMARY KEY,
+    game_id INTEGER,
+    name TEXT NOT NULL,
+    comment TEXT,
+    FOREIGN KEY (game_id) REFERENCES games(id)
+);
+
+CREATE INDEX IF NOT EXISTS alternative_names_game_id ON alternative_names (game_id);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS alternative_names_fts USING fts5(
+    content_rowid="id",
+    content="alternative_names",
+    name
+);
+
+CREATE TABLE IF NOT EXISTS game_localizations (
+    id INTEGER PRIMARY KEY,
+    game_id INTEGER,
+    name TEXT NOT NULL,
+    region INTEGER,
+    FOREIGN KEY (game_id) REFERENCES games(id)
+);
+
+CREATE INDEX IF NOT EXISTS game_localizations_game_id ON game_localizations (game_id);
+
+CREATE VIRTUAL TABLE IF NOT EXISTS game_localizations_fts USING fts5(
+    content_rowid="id",
+    content="game_localizations",
+    name
+);
+
+CREATE TABLE IF NOT EXISTS game_tombstones (
+    game_id INTEGER PRIMARY KEY,
+    name TEXT,
+    merged_into INTEGER,
+    removed_at TEXT DEFAULT (strftime('%Y-%m-%dT%H:%M:%SZ', 'now'))
+);
@@ -0,0 +1,51 @@
+CREATE TRIGGER IF NOT EXISTS games_fts_insert AFTER INSERT ON games BEGIN
+    INSERT INTO games_fts (rowid, name) VALUES (new.id, new.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS games_fts_delete AFTER DELETE ON games BEGIN
+    INSERT INTO games_fts (games_fts, rowid, name) VALUES ('delete', old.id, old.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS games_fts_update AFTER UPDATE OF name ON games BEGIN
+    INSERT INTO games_fts (games_fts, rowid, name) VALUES ('delete', old.id, old.name);
+    INSERT INTO games_fts (rowid, name) VALUES (new.id, new.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS games_trigram_insert AFTER INSERT ON games BEGIN
+    INSERT INTO games_trigram (rowid, name) VALUES (new.id, new.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS games_trigram_delete AFTER DELETE ON games BEGIN
+    INSERT INTO games_trigram (games_trigram, rowid, name) VALUES ('delete', old.id, old.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS games_trigram_update AFTER UPDATE OF name ON games BEGIN
+    INSERT INTO games_trigram (games_trigram, rowid, name) VALUES ('delete', old.id, old.name);
+    INSERT INTO games_trigram (rowid, name) VALUES (new.id, new.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS alternative_names_fts_insert AFTER INSERT ON alternative_names BEGIN
+    INSERT INTO alternative_names_fts (rowid, name) VALUES (new.id, new.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS alternative_names_fts_delete AFTER DELETE ON alternative_names BEGIN
+    INSERT INTO alternative_names_fts (alternative_names_fts, rowid, name) VALUES ('delete', old.id, old.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS alternative_names_fts_update AFTER UPDATE OF name ON alternative_names BEGIN
+    INSERT INTO alternative_names_fts (alternative_names_fts, rowid, name) VALUES ('delete', old.id, old.name);
+    INSERT INTO alternative_names_fts (rowid, name) VALUES (new.id, new.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS game_localizations_fts_insert AFTER INSERT ON game_localizations BEGIN
+    INSERT INTO game_localizations_fts (rowid, name) VALUES (new.id, new.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS game_localizations_fts_delete AFTER DELETE ON game_localizations BEGIN
+    INSERT INTO game_localizations_fts (game_localizations_fts, rowid, name) VALUES ('delete', old.id, old.name);
+END;
+
+CREATE TRIGGER IF NOT EXISTS game_localizations_fts_update AFTER UPDATE OF name ON game_localizations BEGIN
+    INSERT INTO game_localizations_fts (game_localizations_fts, rowid, name) VALUES ('delete', old.id, old.name);
+    INSERT INTO game_localizations_fts (rowid, name) VALUES (new.id, new.name);
+END;
@@ -0,0 +1,2 @@
+ALTER TABLE games ADD COLUMN summary TEXT;
+ALTER TABLE games ADD COLUMN storyline TEXT;
@@ -0,0 +1,219 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use chrono::Utc;
+use image::ImageFormat;
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::State;
+
+use crate::{
+    database::timestamp_from_row,
+    helpers::{create_dir_if_not_exists, get_app_data_directory, remove_file_if_exists},
+    DatabaseConnections, Error,
+};
+
+const THUMBNAIL_SIZE: u32 = 320;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Attachment {
+    pub id: i32,
+    pub log_id: i32,
+    pub created_at: String,
+    pub file_name: String,
+    pub size_bytes: i64,
+    pub path: PathBuf,
+    pub thumbnail_path: Option<PathBuf>,
+}
+
+fn get_attachments_directory(app_handle: &tauri::AppHandle) -> Result<PathBuf, Error> {
+    let attachments_dir = get_app_data_directory(app_handle)?.join("attachments");
+    create_dir_if_not_exists(&attachments_dir)?;
+    Ok(attachments_dir)
+}
+
+fn get_log_attachments_directory(
+    app_handle: &tauri::AppHandle,
+    log_id: i32,
+) -> Result<PathBuf, Error> {
+    Ok(get_attachments_directory(app_handle)?.join(log_id.to_string()))
+}
+
+fn attachment_from_row(
+    row: &rusqlite::Row,
+    attachments_dir: &Path,
+) -> Result<Attachment, rusqlite::Error> {
+    let log_id: i32 = row.get("log_id")?;
+    let log_dir = attachments_dir.join(log_id.to_string());
+    let stored_name: String = row.get("stored_name")?;
+    let thumbnail_name: Option<String> = row.get("thumbnail_name")?;
+    Ok(Attachment {
+        id: row.get("id")?,
+        log_id,
+        created_at: timestamp_from_row(row, "created_at", None)?,
+        file_name: row.get("file_name")?,
+        size_bytes: row.get("size_bytes")?,
+        path: log_dir.join(stored_name),
+        thumbnail_path: thumbnail_name.map(|name| log_dir.join(name)),
+    })
+}
+
+fn create_thumbnail(source: &Path, destination: &Path) -> Result<(), Error> {
+    let image = image::open(source)?;
+    image
+        .thumbnail(THUMBNAIL_SIZE, THUMBNAIL_SIZE)
+        .save_with_format(destination, ImageFormat::Png)?;
+    Ok(())
+}
+
+fn get_attachment_by_id(
+    conn: &Connection,
+    app_handle: &tauri::AppHandle,
+    id: i32,
+) -> Result<Attachment, Error> {
+    let attachments_dir = get_attachments_directory(app_handle)?;
+    let attachment = conn.query_row("SELECT * FROM attachments WHERE id = ?", [id], |row| {
+        attachment_from_row(row, &attachments_dir)
+    })?;
+    Ok(attachment)
+}
+
+/// Removes every attachment file stored for a log. Rows are removed by the caller together with
+/// the log itself.
+pub fn remove_log_attachments(app_handle: &tauri::AppHandle, log_id: i32) -> Result<(), Error> {
+    match fs::remove_dir_all(get_log_attachments_directory(app_handle, log_id)?) {
+        Ok(_) => Ok(()),
+        Err(e) => match e.kind() {
+            std::io::ErrorKind::NotFound => Ok(()),
+            _ => Err(e.into()),
+        },
+    }
+}
+
+#[tauri::command]
+pub fn add_attachment(
+    app_handle: tauri::AppHandle,
+    state: State<DatabaseConnections>,
+    log_id: i32,
+    file_path: PathBuf,
+) -> Result<Attachment, Error> {
+    let log_exists = state
+        .logs_conn
+        .lock()
+        .unwrap()
+        .query_row("SELECT 1 FROM logs WHERE id = ?", [log_id], |_| Ok(()))
+        .optional()?
+        .is_some();
+    if !log_exists {
+        return Err(Error::from(format!("Log {} not found", log_id)));
+    }
+    let file_name = match file_path.file_name() {
+        Some(name) => name.to_string_lossy().to_string(),
+        None => Err(Error::from(format!(
+            "'{}' is not a file",
+            file_path.display()
+        )))?,
+    };
+    let stored_stem = format!(
+        "{}-{:08x}",
+        Utc::now().timestamp_millis(),
+        rand::random::<u32>()
+    );
+    let stored_name = match file_path.extension() {
+        Some(extension) => format!("{}.{}", stored_stem, extension.to_string_lossy()),
+        None => stored_stem.clone(),
+    };
+    let log_dir = get_log_attachments_directory(&app_handle, log_id)?;
+    create_dir_if_not_exists(&log_dir)?;
+    let stored_path = log_dir.join(&stored_name);
+    let size_bytes = fs::copy(&file_path, &stored_path)?;
+    let thumbnail_name = match ImageFormat::from_path(&stored_path) {
+        Ok(_) => {
+            let thumbnail_name = format!("{}-thumbnail.png", stored_stem);
+            match create_thumbnail(&stored_path, &log_dir.join(&thumbnail_name)) {
+                Ok(_) => Some(thumbnail_name),
+                Err(_) => None,
+            }
+        }
+        Err(_) => None,
+    };
+    let conn = state.logs_conn.lock().unwrap();
+    let insert_result = conn.execute(
+        "INSERT INTO attachments (log_id, file_name, stored_name, thumbnail_name, size_bytes) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![log_id, file_name, stored_name, thumbnail_name, size_bytes as i64],
+    );
+    if let Err(e) = insert_result {
+        let _ = fs::remove_file(&stored_path);
+        if let Some(thumbnail_name) = &thumbnail_name {
+            let _ = fs::remove_file(log_dir.join(thumbnail_name));
+        }
+        return Err(e.into());
+    }
+    let id = conn.last_insert_rowid() as i32;
+    get_attachment_by_id(&conn, &app_handle, id)
+}
+
+#[tauri::command]
+pub fn get_attachments(
+    app_handle: tauri::AppHandle,
+    state: State<DatabaseConnections>,
+    log_id: i32,
+) -> Result<Vec<Attachment>, Error> {
+    let conn = state.logs_conn.lock().unwrap();
+    let attachments_dir = get_attachments_directory(&app_handle)?;
+    let mut stmt =
+        conn.prepare("SELECT * FROM attachments WHERE log_id = ? ORDER BY created_at, id")?;
+    let attachments = stmt
+        .query_map([log_id], |row| attachment_from_row(row, &attachments_dir))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(attachments)
+}
+
+#[tauri::command]
+pub fn remove_attachment(
+    app_handle: tauri::AppHandle,
+    state: State<DatabaseConnections>,
+    id: i32,
+) -> Result<i32, Error> {
+    let conn = state.logs_conn.lock().unwrap();
+    let attachment = get_attachment_by_id(&conn, &app_handle, id)?;
+    conn.execute("DELETE FROM attachments WHERE id = ?", [id])?;
+    remove_file_if_exists(&attachment.path)?;
+    if let Some(thumbnail_path) = &attachment.thumbnail_path {
+        remove_file_if_exists(thumbnail_path)?;
+    }
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn export_attachments(
+    app_handle: tauri::AppHandle,
+    state: State<DatabaseConnections>,
+    log_id: i32,
+    to_directory: PathBuf,
+) -> Result<Vec<PathBuf>, Error> {
+    let attachments = get_attachments(app_handle, state, log_id)?;
+    create_dir_if_not_exists(&to_directory)?;
+    let mut exported_paths = vec![];
+    for attachment in attachments {
+        let file_name = Path::new(&attachment.file_name);
+        let stem = file_name
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let extension = file_name
+            .extension()
+            .map(|extension| format!(".{}", extension.to_string_lossy()))
+            .unwrap_or_default();
+        let mut export_path = to_directory.join(&attachment.file_name);
+        let mut copy_number = 1;
+        while export_path.exists() {
+            export_path = to_directory.join(format!("{} ({}){}", stem, copy_number, extension));
+            copy_number += 1;
+        }
+        fs::copy(&attachment.path, &export_path)?;
+        exported_paths.push(export_path);
+    }
+    Ok(exported_paths)
+}
@@ -0,0 +1,101 @@
+use std::collections::HashSet;
+
+use chrono::{Duration, SecondsFormat, Utc};
+use rusqlite::Connection;
+use tauri::State;
+
+use crate::{database::LATEST_LOGS, DatabaseConnections, Error};
+
+const RECENT_PLAY_WEEKS: i64 = 8;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct BacklogEstimate {
+    pub total_games: i32,
+    pub estimated_games: i32,
+    pub total_hours: f32,
+    pub weekly_hours: f32,
+    pub weeks_to_finish: Option<f32>,
+}
+
+fn load_backlog_games(conn: &Connection) -> Result<HashSet<i32>, Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT game_id FROM ({}) WHERE status = 'backlog'",
+        LATEST_LOGS
+    ))?;
+    let backlog_games = stmt
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<_, _>>()?;
+    Ok(backlog_games)
+}
+
+#[tauri::command]
+pub fn get_backlog_estimate(
+    state: State<'_, DatabaseConnections>,
+) -> Result<BacklogEstimate, Error> {
+    let backlog_games: HashSet<i32>;
+    let recent_minutes_played: i32;
+    {
+        let conn = state.logs_conn.lock().unwrap();
+        backlog_games = load_backlog_games(&conn)?;
+        let recent_cutoff = (Utc::now() - Duration::weeks(RECENT_PLAY_WEEKS))
+            .to_rfc3339_opts(SecondsFormat::Secs, true);
+        recent_minutes_played = conn.query_row(
+            "SELECT COALESCE(SUM(minutes_played), 0) FROM logs WHERE end_date >= ? AND status != 'wishlist'",
+            [recent_cutoff],
+            |row| row.get(0),
+        )?;
+    }
+    let mut estimated_games = 0;
+    let mut total_seconds: i64 = 0;
+    {
+        let conn = state.igdb_conn.lock().unwrap();
+        let mut stmt = conn.prepare(&format!(
+            "SELECT COALESCE(normally, hastily, completely) FROM game_time_to_beats WHERE game_id IN ({}) GROUP BY game_id",
+            backlog_games
+                .iter()
+                .map(|id| id.to_string())
+                .collect::<Vec<String>>()
+                .join(",")
+        ))?;
+        for seconds in stmt.query_map([], |row| row.get::<usize, Option<i64>>(0))? {
+            if let Some(seconds) = seconds? {
+                estimated_games += 1;
+                total_seconds += seconds;
+            }
+        }
+    }
+    let total_hours = total_seconds as f32 / 3600.0;
+    let weekly_hours = recent_minutes_played as f32 / 60.0 / RECENT_PLAY_WEEKS as f32;
+    Ok(BacklogEstimate {
+        total_games: backlog_games.len() as i32,
+        estimated_games,
+        total_hours,
+        weekly_hours,
+        weeks_to_finish: match weekly_hours {
+            hours if hours > 0.0 => Some(total_hours / hours),
+            _ => None,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backlog_ignores_games_finished_since() {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../sql/initialize_database.sql"))
+            .unwrap();
+        conn.execute_batch(
+            "INSERT INTO logs (id, game_id, created_at, status) VALUES
+                (1, 10, '2024-01-01T00:00:00Z', 'backlog'),
+                (2, 10, '2024-03-01T00:00:00Z', 'completed'),
+                (3, 20, '2024-01-01T00:00:00Z', 'backlog'),
+                (4, 20, '2024-01-01T00:00:00Z', 'backlog');",
+        )
+        .unwrap();
+
+        assert_eq!(load_backlog_games(&conn).unwrap(), HashSet::from([20]));
+    }
+}
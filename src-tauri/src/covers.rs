@@ -0,0 +1,250 @@
+use std::{
+    collections::HashMap,
+    fs::{self, File},
+    path::{Path, PathBuf},
+    sync::{Arc, Mutex},
+    thread,
+    time::SystemTime,
+};
+
+use tauri::{
+    http::{header, Request, Response, StatusCode},
+    Manager, UriSchemeContext, UriSchemeResponder,
+};
+
+use crate::{
+    helpers::{create_dir_if_not_exists, get_app_data_directory, get_user_settings},
+    DatabaseConnections, Error,
+};
+
+pub const COVER_URI_SCHEME: &str = "cover";
+pub const DEFAULT_COVER_CDN: &str = "https://images.igdb.com/igdb/image/upload/t_cover_big/";
+const COVER_CACHE_LIMIT_BYTES: u64 = 256 * 1024 * 1024;
+
+struct CacheEntry {
+    size: u64,
+    last_used: SystemTime,
+}
+
+/// Covers are stored once per image id as `{image_id}.jpg` in the app data directory. The index
+/// is read from disk on first use; file modification times hold the last use, so the LRU order
+/// survives restarts.
+#[derive(Default)]
+pub struct CoverCache {
+    index: Mutex<Option<HashMap<String, CacheEntry>>>,
+    downloads: Mutex<HashMap<String, Arc<Mutex<()>>>>,
+}
+
+impl CoverCache {
+    fn with_index<T>(
+        &self,
+        directory: &Path,
+        f: impl FnOnce(&mut HashMap<String, CacheEntry>) -> T,
+    ) -> Result<T, Error> {
+        let mut index = self.index.lock().unwrap();
+        if index.is_none() {
+            *index = Some(load_index(directory)?);
+        }
+        Ok(f(index.as_mut().unwrap()))
+    }
+
+    /// Serialises work on one image id, so concurrent requests for the same cover download it once.
+    fn download_lock(&self, image_id: &str) -> Arc<Mutex<()>> {
+        self.downloads
+            .lock()
+            .unwrap()
+            .entry(image_id.to_string())
+            .or_default()
+            .clone()
+    }
+
+    /// Forgets the lock once no other request holds it. Clones are only handed out under the
+    /// outer lock, so the count can't grow while it is checked.
+    fn release_download_lock(&self, image_id: &str, download_lock: Arc<Mutex<()>>) {
+        let mut downloads = self.downloads.lock().unwrap();
+        drop(download_lock);
+        if downloads
+            .get(image_id)
+            .is_some_and(|lock| Arc::strong_count(lock) == 1)
+        {
+            downloads.remove(image_id);
+        }
+    }
+}
+
+fn load_index(directory: &Path) -> Result<HashMap<String, CacheEntry>, Error> {
+    let mut index = HashMap::new();
+    for entry in fs::read_dir(directory)? {
+        let path = entry?.path();
+        if path.extension().and_then(|e| e.to_str()) != Some("jpg") {
+            continue;
+        }
+        let (Some(image_id), Ok(metadata)) = (
+            path.file_stem().and_then(|s| s.to_str()),
+            fs::metadata(&path),
+        ) else {
+            continue;
+        };
+        index.insert(
+            image_id.to_string(),
+            CacheEntry {
+                size: metadata.len(),
+                last_used: metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH),
+            },
+        );
+    }
+    Ok(index)
+}
+
+fn get_covers_directory(app_handle: &tauri::AppHandle) -> Result<PathBuf, Error> {
+    let covers_dir = get_app_data_directory(app_handle)?.join("covers");
+    create_dir_if_not_exists(&covers_dir)?;
+    Ok(covers_dir)
+}
+
+fn is_valid_image_id(image_id: &str) -> bool {
+    !image_id.is_empty()
+        && image_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-')
+}
+
+/// Removes the least recently used covers until the cache fits in its size limit.
+fn evict(directory: &Path, index: &mut HashMap<String, CacheEntry>) {
+    let mut total_bytes: u64 = index.values().map(|entry| entry.size).sum();
+    if total_bytes <= COVER_CACHE_LIMIT_BYTES {
+        return;
+    }
+    let mut entries = index
+        .iter()
+        .map(|(image_id, entry)| (entry.last_used, entry.size, image_id.clone()))
+        .collect::<Vec<_>>();
+    entries.sort();
+    for (_, size, image_id) in entries {
+        if total_bytes <= COVER_CACHE_LIMIT_BYTES {
+            break;
+        }
+        if fs::remove_file(directory.join(format!("{}.jpg", image_id))).is_ok() {
+            index.remove(&image_id);
+            total_bytes -= size;
+        }
+    }
+}
+
+fn download_cover(app_handle: &tauri::AppHandle, image_id: &str, path: &Path) -> Result<(), Error> {
+    let cdn = get_user_settings(app_handle.clone())?.cover_cdn;
+    let url = format!("{}/{}.jpg", cdn.trim_end_matches('/'), image_id);
+    let bytes = reqwest::blocking::get(url)?.error_for_status()?.bytes()?;
+    let partial_path = path.with_extension("jpg.part");
+    fs::write(&partial_path, &bytes)?;
+    fs::rename(&partial_path, path)?;
+    Ok(())
+}
+
+/// Returns the cover for an image id, downloading it first when it isn't cached yet.
+pub fn get_cover(app_handle: &tauri::AppHandle, image_id: &str) -> Result<Vec<u8>, Error> {
+    if !is_valid_image_id(image_id) {
+        return Err(Error::from(format!(
+            "Invalid cover image id '{}'",
+            image_id
+        )));
+    }
+    let cache = app_handle.state::<CoverCache>();
+    let directory = get_covers_directory(app_handle)?;
+    let path = directory.join(format!("{}.jpg", image_id));
+    let download_lock = cache.download_lock(image_id);
+    let result = (|| {
+        let _guard = download_lock.lock().unwrap();
+        let cached =
+            cache.with_index(&directory, |index| index.contains_key(image_id))? && path.exists();
+        if !cached {
+            download_cover(app_handle, image_id, &path)?;
+        }
+        let bytes = fs::read(&path)?;
+        let now = SystemTime::now();
+        let _ = File::options()
+            .write(true)
+            .open(&path)
+            .and_then(|file| file.set_modified(now));
+        cache.with_index(&directory, |index| {
+            index.insert(
+                image_id.to_string(),
+                CacheEntry {
+                    size: bytes.len() as u64,
+                    last_used: now,
+                },
+            );
+            if !cached {
+                evict(&directory, index);
+            }
+        })?;
+        Ok(bytes)
+    })();
+    cache.release_download_lock(image_id, download_lock);
+    result
+}
+
+/// Serves `cover://localhost/{image_id}` from the cache.
+pub fn handle_cover_request(
+    ctx: UriSchemeContext<'_, tauri::Wry>,
+    request: Request<Vec<u8>>,
+    responder: UriSchemeResponder,
+) {
+    let app_handle = ctx.app_handle().clone();
+    let image_id = request
+        .uri()
+        .path()
+        .trim_start_matches('/')
+        .trim_end_matches(".jpg")
+        .to_string();
+    thread::spawn(move || {
+        let response = match get_cover(&app_handle, &image_id) {
+            Ok(bytes) => Response::builder()
+                .header(header::CONTENT_TYPE, "image/jpeg")
+                .header(header::CACHE_CONTROL, "max-age=31536000, immutable")
+                .body(bytes),
+            Err(e) => Response::builder()
+                .status(StatusCode::NOT_FOUND)
+                .header(header::CONTENT_TYPE, "text/plain")
+                .body(e.to_string().into_bytes()),
+        };
+        if let Ok(response) = response {
+            responder.respond(response);
+        }
+    });
+}
+
+fn get_journal_cover_ids(app_handle: &tauri::AppHandle) -> Result<Vec<String>, Error> {
+    let connections = app_handle.state::<DatabaseConnections>();
+    let game_ids = {
+        let conn = connections.logs_conn.lock().unwrap();
+        let game_ids = conn
+            .prepare("SELECT DISTINCT game_id FROM logs")?
+            .query_map([], |row| row.get::<_, i32>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        game_ids
+    };
+    let conn = connections.igdb_conn.lock().unwrap();
+    let image_ids = conn
+        .prepare("SELECT DISTINCT c.image_id FROM games g JOIN covers c ON c.id = g.cover_id WHERE g.id IN (SELECT value FROM json_each(?1))")?
+        .query_map([serde_json::to_string(&game_ids)?], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(image_ids)
+}
+
+/// Caches the cover of every game in the journal in the background, so they show up offline.
+pub fn prefetch_journal_covers(app_handle: tauri::AppHandle) {
+    thread::spawn(move || {
+        let Ok(image_ids) = get_journal_cover_ids(&app_handle) else {
+            return;
+        };
+        for image_id in image_ids {
+            if let Err(Error::Reqwest(e)) = get_cover(&app_handle, &image_id) {
+                // Offline, the next launch tries again.
+                if e.is_connect() || e.is_timeout() {
+                    return;
+                }
+            }
+        }
+    });
+}
@@ -3,8 +3,13 @@ use std::{
     thread,
 };
 
-use crate::{database::LogData, igdb::get_games_from_links, DatabaseConnections};
-use chrono::{DateTime, Local};
+use crate::{
+    database::{to_storage_timestamp, LogData},
+    helpers::get_user_settings,
+    igdb::get_games_from_links,
+    DatabaseConnections,
+};
+use chrono::{DateTime, Local, SecondsFormat};
 use reqwest::Client;
 use rusqlite::params;
 use tauri::{Emitter, Manager, State};
@@ -111,7 +116,12 @@ pub async fn get_steam_data(
         .iter()
         .map(|s_game| format!("https://store.steampowered.com/app/{}", s_game.appid))
         .collect::<Vec<String>>();
-    let games = get_games_from_links(app_handle.state::<DatabaseConnections>(), steam_links)?;
+    let content_filter = get_user_settings(app_handle.clone())?.content_filter;
+    let games = get_games_from_links(
+        app_handle.state::<DatabaseConnections>(),
+        &content_filter,
+        steam_links,
+    )?;
     for steam_game in owned_steam_games_response.games {
         let igdb_game = match games.iter().find(|g| {
             g.websites.iter().any(|w| {
@@ -136,7 +146,7 @@ pub async fn get_steam_data(
                 },
                 None => Local::now(),
             };
-        let formatted_date = date.format("%Y-%m-%d %H:%M:%S").to_string();
+        let formatted_date = date.to_rfc3339_opts(SecondsFormat::Secs, false);
         logs_data.push(LogData {
             game_id: igdb_game.id,
             start_date: formatted_date.clone(),
@@ -200,12 +210,16 @@ pub fn import_igdb_games(
     let logs_transaction = conn.transaction()?;
     {
         let mut stmt = logs_transaction.prepare(
-            "INSERT INTO logs (start_date, end_date, status, minutes_played, notes, game_id) VALUES (?, ?, ?, ?, ?, ?)",
+            "INSERT INTO logs (start_date, start_date_offset, end_date, end_date_offset, status, minutes_played, notes, game_id) VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
         )?;
         for log_data in &data {
+            let (start_date, start_date_offset) = to_storage_timestamp(&log_data.start_date)?;
+            let (end_date, end_date_offset) = to_storage_timestamp(&log_data.end_date)?;
             stmt.execute(params![
-                &log_data.start_date,
-                &log_data.end_date,
+                start_date,
+                start_date_offset,
+                end_date,
+                end_date_offset,
                 &log_data.status,
                 &log_data.minutes_played,
                 &log_data.notes,
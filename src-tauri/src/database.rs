@@ -1,9 +1,21 @@
-use std::collections::HashMap;
+use std::{
+    collections::HashMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
 
-use rusqlite::{Connection, OptionalExtension};
+use chrono::{
+    DateTime, FixedOffset, Local, NaiveDate, NaiveDateTime, Offset, SecondsFormat, TimeZone, Utc,
+};
+use rusqlite::{params, Connection, OpenFlags, OptionalExtension};
 
 use crate::{
-    helpers::{create_dir_if_not_exists, get_app_data_directory, SchemaFieldUpdate},
+    attachments::remove_log_attachments,
+    helpers::{
+        create_dir_if_not_exists, get_app_data_directory, remove_file_if_exists, SchemaFieldUpdate,
+    },
+    reviews::{get_review_score_averages, ReviewScoreAverage},
     DatabaseConnections, Error,
 };
 use tauri::State;
@@ -19,6 +31,7 @@ pub struct DashboardStatistics {
     pub total_minutes_played: i32,
     pub total_games_played: i32,
     pub total_games_completed: i32,
+    pub review_score_averages: Vec<ReviewScoreAverage>,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -118,28 +131,393 @@ impl SchemaModifier {
     }
 }
 
+const LOGS_DATABASE_VERSION: i32 = 1;
+const IGDB_DATABASE_FILE: &str = "igdb.db";
+const IGDB_STAGING_FILE: &str = "igdb.staging.db";
+const IGDB_READY_FILE: &str = "igdb.ready.db";
+const IGDB_PREVIOUS_FILE: &str = "igdb.previous.db";
+const IGDB_DATABASE_VERSION: i32 = 3;
+const SEARCH_INDEXES: [(&str, &str); 4] = [
+    ("games_fts", "games"),
+    ("games_trigram", "games"),
+    ("alternative_names_fts", "alternative_names"),
+    ("game_localizations_fts", "game_localizations"),
+];
+/// Each game's most recent log, which holds its current status.
+pub const LATEST_LOGS: &str = "SELECT * FROM (SELECT *, ROW_NUMBER() OVER (PARTITION BY game_id ORDER BY created_at DESC, id DESC) AS log_position FROM logs) WHERE log_position = 1";
+
 pub fn initialize_database(
     app_handle: tauri::AppHandle,
 ) -> Result<(rusqlite::Connection, rusqlite::Connection), Error> {
     let data_dir = get_app_data_directory(&app_handle)?;
     create_dir_if_not_exists(data_dir.as_path())?;
-    let logs_conn = Connection::open(data_dir.join("logs.db"))?;
+    let mut logs_conn = Connection::open(data_dir.join("logs.db"))?;
+    let is_new_database = logs_conn
+        .query_row(
+            "SELECT name FROM sqlite_master WHERE type='table' AND name='logs'",
+            [],
+            |row| row.get::<usize, String>(0),
+        )
+        .optional()?
+        .is_none();
     let sql_file_contents = include_str!("../sql/initialize_database.sql");
     logs_conn.execute_batch(sql_file_contents)?;
-    let conn = Connection::open(data_dir.join("igdb.db"))?;
+    if is_new_database {
+        logs_conn.pragma_update(None, "user_version", LOGS_DATABASE_VERSION)?;
+    } else {
+        migrate_logs_database(&mut logs_conn)?;
+    }
+    recover_igdb_files(&data_dir)?;
+    let conn = Connection::open(data_dir.join(IGDB_DATABASE_FILE))?;
+    let is_new_igdb_database = conn
+        .query_row(
+            "SELECT name FROM sqlite_master WHERE type='table' AND name='games'",
+            [],
+            |row| row.get::<usize, String>(0),
+        )
+        .optional()?
+        .is_none();
+    let igdb_version = if is_new_igdb_database {
+        IGDB_DATABASE_VERSION
+    } else {
+        migrate_igdb_database(&conn, &data_dir)?
+    };
     let igdb_sql_file_contents = include_str!("../sql/initialize_igdb_database.sql");
     conn.execute_batch(igdb_sql_file_contents)?;
+    conn.execute_batch(include_str!("../sql/initialize_search_triggers.sql"))?;
+    // Before version 2 the search indexes were only rebuilt on launch, not kept in sync. Since
+    // version 3 imports rebuild them, so the version alone says they are current.
+    if igdb_version < 3 {
+        check_search_indexes(&conn, igdb_version < 2)?;
+    }
+    conn.pragma_update(None, "user_version", IGDB_DATABASE_VERSION)?;
     Ok((logs_conn, conn))
 }
 
-fn log_from_row(row: &rusqlite::Row) -> Result<Log, rusqlite::Error> {
+/// Comparing row counts and the highest id is cheap, unlike FTS5's own integrity check, which
+/// re-tokenises every row.
+fn check_search_indexes(conn: &Connection, force_rebuild: bool) -> Result<(), Error> {
+    for (index, table) in SEARCH_INDEXES {
+        let drifted = force_rebuild
+            || conn.query_row(
+                &format!(
+                    "SELECT (SELECT COUNT(*) FROM {table}) != (SELECT COUNT(*) FROM {index}_docsize) OR (SELECT MAX(id) FROM {table}) IS NOT (SELECT MAX(id) FROM {index}_docsize)"
+                ),
+                [],
+                |row| row.get::<_, bool>(0),
+            )?;
+        if drifted {
+            conn.execute(
+                &format!("INSERT INTO {index} ({index}) VALUES ('rebuild')"),
+                [],
+            )?;
+        }
+    }
+    Ok(())
+}
+
+/// Bulk imports drop the triggers that keep the search indexes in sync, so each index is built
+/// once at the end rather than row by row.
+pub fn drop_search_triggers(conn: &Connection) -> Result<(), Error> {
+    for (index, _) in SEARCH_INDEXES {
+        for change in ["insert", "delete", "update"] {
+            conn.execute(&format!("DROP TRIGGER IF EXISTS {index}_{change}"), [])?;
+        }
+    }
+    Ok(())
+}
+
+pub fn rebuild_search_indexes(conn: &Connection) -> Result<(), Error> {
+    for (index, _) in SEARCH_INDEXES {
+        conn.execute(
+            &format!("INSERT INTO {index} ({index}) VALUES ('rebuild')"),
+            [],
+        )?;
+    }
+    conn.execute_batch(include_str!("../sql/initialize_search_triggers.sql"))?;
+    conn.pragma_update(None, "user_version", IGDB_DATABASE_VERSION)?;
+    Ok(())
+}
+
+fn recover_igdb_files(data_dir: &Path) -> Result<(), Error> {
+    let live_path = data_dir.join(IGDB_DATABASE_FILE);
+    if !live_path.exists() {
+        for candidate in [IGDB_READY_FILE, IGDB_PREVIOUS_FILE] {
+            let candidate_path = data_dir.join(candidate);
+            if candidate_path.exists() && fs::rename(&candidate_path, &live_path).is_ok() {
+                break;
+            }
+        }
+    }
+    remove_file_if_exists(&data_dir.join(IGDB_STAGING_FILE))?;
+    remove_file_if_exists(&data_dir.join(IGDB_READY_FILE))?;
+    Ok(())
+}
+
+/// The copy is made through its own read-only connection, so the app keeps reading while it runs.
+pub fn create_igdb_staging(data_dir: &Path) -> Result<PathBuf, Error> {
+    let staging_path = data_dir.join(IGDB_STAGING_FILE);
+    remove_file_if_exists(&staging_path)?;
+    let conn = Connection::open_with_flags(
+        data_dir.join(IGDB_DATABASE_FILE),
+        OpenFlags::SQLITE_OPEN_READ_ONLY,
+    )?;
+    conn.execute(
+        "VACUUM INTO ?1",
+        [staging_path.to_string_lossy().to_string()],
+    )?;
+    Ok(staging_path)
+}
+
+pub fn verify_igdb_database(conn: &Connection) -> Result<(), Error> {
+    let problems = conn
+        .prepare("PRAGMA quick_check")?
+        .query_map([], |row| row.get::<_, String>(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    if problems != ["ok"] {
+        return Err(Error::from(format!(
+            "The imported catalogue is corrupt: {}",
+            problems.join("; ")
+        )));
+    }
+    let has_games = conn.query_row("SELECT EXISTS (SELECT 1 FROM games)", [], |row| {
+        row.get::<_, bool>(0)
+    })?;
+    if !has_games {
+        return Err(Error::from("The imported catalogue has no games"));
+    }
+    Ok(())
+}
+
+/// Closes the live connection, runs `f` on the database files and reopens the connection on
+/// whatever ended up as igdb.db, even when `f` failed halfway.
+fn with_igdb_closed(
+    data_dir: &Path,
+    igdb_conn: &Mutex<Connection>,
+    f: impl FnOnce(&Path) -> Result<(), Error>,
+) -> Result<(), Error> {
+    let mut conn = igdb_conn.lock().unwrap();
+    let live_conn = std::mem::replace(&mut *conn, Connection::open_in_memory()?);
+    // Windows refuses to rename a file that is still open.
+    if let Err((live_conn, e)) = live_conn.close() {
+        *conn = live_conn;
+        return Err(e.into());
+    }
+    let result = f(&data_dir.join(IGDB_DATABASE_FILE));
+    let recovered = recover_igdb_files(data_dir);
+    *conn = Connection::open(data_dir.join(IGDB_DATABASE_FILE))?;
+    result.and(recovered)
+}
+
+pub fn swap_igdb_database(
+    data_dir: &Path,
+    igdb_conn: &Mutex<Connection>,
+    staging_path: &Path,
+) -> Result<(), Error> {
+    let ready_path = data_dir.join(IGDB_READY_FILE);
+    fs::rename(staging_path, &ready_path)?;
+    with_igdb_closed(data_dir, igdb_conn, |live_path| {
+        let previous_path = data_dir.join(IGDB_PREVIOUS_FILE);
+        remove_file_if_exists(&previous_path)?;
+        fs::rename(live_path, &previous_path)?;
+        fs::rename(&ready_path, live_path)?;
+        Ok(())
+    })
+}
+
+pub fn has_previous_igdb_database(data_dir: &Path) -> bool {
+    data_dir.join(IGDB_PREVIOUS_FILE).exists()
+}
+
+pub fn rollback_igdb_database(data_dir: &Path, igdb_conn: &Mutex<Connection>) -> Result<(), Error> {
+    if !has_previous_igdb_database(data_dir) {
+        return Err(Error::from(
+            "There is no previous catalogue to roll back to",
+        ));
+    }
+    with_igdb_closed(data_dir, igdb_conn, |live_path| {
+        fs::rename(data_dir.join(IGDB_PREVIOUS_FILE), live_path)?;
+        Ok(())
+    })
+}
+
+/// Migrations that add columns to existing igdb tables also forget the local dump versions, so
+/// the next update downloads full dumps and fills them in. Returns the version the database was
+/// at before migrating.
+fn migrate_igdb_database(conn: &Connection, data_dir: &Path) -> Result<i32, Error> {
+    let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version < 1 {
+        conn.execute_batch(include_str!("../sql/migrations/igdb_001_game_metadata.sql"))?;
+        match fs::remove_file(data_dir.join("dump_versions.toml")) {
+            Ok(_) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+    Ok(version)
+}
+
+fn migrate_logs_database(conn: &mut Connection) -> Result<(), Error> {
+    let version: i32 = conn.query_row("PRAGMA user_version", [], |row| row.get(0))?;
+    if version < 1 {
+        migrate_to_utc_timestamps(conn)?;
+    }
+    conn.pragma_update(None, "user_version", LOGS_DATABASE_VERSION)?;
+    Ok(())
+}
+
+/// Rewrites every stored date as a UTC instant. Log dates were written as naive local times, so
+/// the local offset at that time is kept as the original offset; row timestamps came from
+/// `CURRENT_TIMESTAMP` and are already UTC.
+fn migrate_to_utc_timestamps(conn: &mut Connection) -> Result<(), Error> {
+    let transaction = conn.transaction()?;
+    transaction.execute_batch(include_str!("../sql/migrations/001_utc_timestamps.sql"))?;
+    {
+        let mut select_logs_stmt = transaction.prepare("SELECT id, game_id, created_at, updated_at, start_date, end_date, rating, notes, status, minutes_played FROM logs")?;
+        let mut insert_log_stmt = transaction.prepare("INSERT INTO new_logs (id, game_id, created_at, updated_at, start_date, start_date_offset, end_date, end_date_offset, rating, notes, status, minutes_played) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12)")?;
+        let mut rows = select_logs_stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let created_at = legacy_timestamp(row.get(2)?, &Utc)?;
+            let updated_at = legacy_timestamp(row.get(3)?, &Utc)?;
+            let start_date = legacy_timestamp(row.get(4)?, &Local)?;
+            let end_date = legacy_timestamp(row.get(5)?, &Local)?;
+            insert_log_stmt.execute(params![
+                row.get::<usize, i32>(0)?,
+                row.get::<usize, i32>(1)?,
+                created_at.0,
+                updated_at.0,
+                start_date.0,
+                start_date.1,
+                end_date.0,
+                end_date.1,
+                row.get::<usize, Option<i32>>(6)?,
+                row.get::<usize, Option<String>>(7)?,
+                row.get::<usize, Option<String>>(8)?,
+                row.get::<usize, Option<i32>>(9)?,
+            ])?;
+        }
+        let mut select_executables_stmt = transaction.prepare(
+            "SELECT id, created_at, updated_at, executable_name, game_id FROM executable_details",
+        )?;
+        let mut insert_executable_stmt = transaction.prepare("INSERT INTO new_executable_details (id, created_at, updated_at, executable_name, game_id) VALUES (?1, ?2, ?3, ?4, ?5)")?;
+        let mut rows = select_executables_stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let created_at = legacy_timestamp(row.get(1)?, &Utc)?;
+            let updated_at = legacy_timestamp(row.get(2)?, &Utc)?;
+            insert_executable_stmt.execute(params![
+                row.get::<usize, i32>(0)?,
+                created_at.0,
+                updated_at.0,
+                row.get::<usize, Option<String>>(3)?,
+                row.get::<usize, i32>(4)?,
+            ])?;
+        }
+        let mut select_settings_stmt = transaction.prepare("SELECT id, created_at, updated_at, username, executable_paths, process_monitoring_enabled, process_monitoring_directory_depth FROM user_settings")?;
+        let mut insert_settings_stmt = transaction.prepare("INSERT INTO new_user_settings (id, created_at, updated_at, username, executable_paths, process_monitoring_enabled, process_monitoring_directory_depth) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)")?;
+        let mut rows = select_settings_stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let created_at = legacy_timestamp(row.get(1)?, &Utc)?;
+            let updated_at = legacy_timestamp(row.get(2)?, &Utc)?;
+            insert_settings_stmt.execute(params![
+                row.get::<usize, i32>(0)?,
+                created_at.0,
+                updated_at.0,
+                row.get::<usize, Option<String>>(3)?,
+                row.get::<usize, Option<String>>(4)?,
+                row.get::<usize, Option<i32>>(5)?,
+                row.get::<usize, Option<i32>>(6)?,
+            ])?;
+        }
+    }
+    transaction.execute_batch(
+        "DROP TABLE logs;
+        ALTER TABLE new_logs RENAME TO logs;
+        DROP TABLE executable_details;
+        ALTER TABLE new_executable_details RENAME TO executable_details;
+        DROP TABLE user_settings;
+        ALTER TABLE new_user_settings RENAME TO user_settings;",
+    )?;
+    transaction.commit()?;
+    Ok(())
+}
+
+/// Converts a date written before timestamps were stored as UTC. Values without an offset are
+/// read in `naive_timezone`. Missing values become the current time, matching the old defaults.
+fn legacy_timestamp<Tz: TimeZone>(
+    value: Option<String>,
+    naive_timezone: &Tz,
+) -> Result<(String, i32), Error> {
+    let value = match value {
+        Some(value) => value,
+        None => return Ok((Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true), 0)),
+    };
+    if let Ok(timestamp) = DateTime::parse_from_rfc3339(&value) {
+        return Ok(storage_timestamp(&timestamp));
+    }
+    let naive = NaiveDateTime::parse_from_str(&value, "%Y-%m-%d %H:%M:%S")
+        .or_else(|_| NaiveDateTime::parse_from_str(&value, "%Y-%m-%dT%H:%M:%S%.f"))
+        .or_else(|_| {
+            NaiveDate::parse_from_str(&value, "%Y-%m-%d")
+                .map(|date| date.and_hms_opt(0, 0, 0).unwrap_or_default())
+        })?;
+    let timestamp = match naive_timezone.from_local_datetime(&naive).earliest() {
+        Some(timestamp) => timestamp.fixed_offset(),
+        None => naive.and_utc().fixed_offset(),
+    };
+    Ok(storage_timestamp(&timestamp))
+}
+
+fn storage_timestamp(timestamp: &DateTime<FixedOffset>) -> (String, i32) {
+    (
+        timestamp
+            .with_timezone(&Utc)
+            .to_rfc3339_opts(SecondsFormat::Secs, true),
+        timestamp.offset().fix().local_minus_utc(),
+    )
+}
+
+/// Parses an RFC 3339 timestamp into the stored form: the UTC instant and the original offset in
+/// seconds.
+pub fn to_storage_timestamp(value: &str) -> Result<(String, i32), Error> {
+    let timestamp = DateTime::parse_from_rfc3339(value)?;
+    Ok(storage_timestamp(&timestamp))
+}
+
+/// Turns a stored UTC instant back into an RFC 3339 timestamp in its original offset.
+pub fn from_storage_timestamp(utc: &str, offset: i32) -> Result<String, Error> {
+    let timestamp = DateTime::parse_from_rfc3339(utc)?;
+    let offset = match FixedOffset::east_opt(offset) {
+        Some(offset) => offset,
+        None => Err(Error::from(format!("Invalid UTC offset: {}", offset)))?,
+    };
+    Ok(timestamp
+        .with_timezone(&offset)
+        .to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+pub fn timestamp_from_row(
+    row: &rusqlite::Row,
+    column: &str,
+    offset_column: Option<&str>,
+) -> Result<String, rusqlite::Error> {
+    let utc: String = row.get(column)?;
+    let offset = match offset_column {
+        Some(offset_column) => row.get::<&str, Option<i32>>(offset_column)?.unwrap_or(0),
+        None => 0,
+    };
+    from_storage_timestamp(&utc, offset).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+    })
+}
+
+pub fn log_from_row(row: &rusqlite::Row) -> Result<Log, rusqlite::Error> {
     Ok(Log {
         id: row.get("id")?,
         game_id: row.get("game_id")?,
-        created_at: row.get("created_at")?,
-        updated_at: row.get("updated_at")?,
-        start_date: row.get("start_date")?,
-        end_date: row.get("end_date")?,
+        created_at: timestamp_from_row(row, "created_at", None)?,
+        updated_at: timestamp_from_row(row, "updated_at", None)?,
+        start_date: timestamp_from_row(row, "start_date", Some("start_date_offset"))?,
+        end_date: timestamp_from_row(row, "end_date", Some("end_date_offset"))?,
         rating: row.get("rating")?,
         notes: row.get("notes")?,
         status: row.get("status")?,
@@ -170,6 +548,8 @@ pub fn get_dashboard_statistics(
     end_date: String,
 ) -> Result<DashboardStatistics, Error> {
     let conn = state.logs_conn.lock().unwrap();
+    let (start_date, _) = to_storage_timestamp(&start_date)?;
+    let (end_date, _) = to_storage_timestamp(&end_date)?;
     let minutes_and_games_played_stmt = conn.prepare("SELECT COALESCE(SUM(total_minutes_played), 0), COUNT(*) FROM ( SELECT COALESCE(SUM(minutes_played), 0) AS total_minutes_played FROM logs WHERE (end_date BETWEEN ?1 AND ?2) AND status != 'wishlist' GROUP BY game_id ) AS subquery;").optional()?;
     let this_minutes_and_games_played: (i32, i32) = match minutes_and_games_played_stmt {
         Some(mut stmt) => stmt.query_row([start_date.clone(), end_date.clone()], |row| {
@@ -184,10 +564,12 @@ pub fn get_dashboard_statistics(
         completed_games_stmt.query_row([start_date.clone(), end_date.clone()], |row| {
             Ok(row.get(0)?)
         })?;
+    let review_score_averages = get_review_score_averages(&conn, &start_date, &end_date)?;
     Ok(DashboardStatistics {
         total_minutes_played: this_minutes_and_games_played.0,
         total_games_played: this_minutes_and_games_played.1,
         total_games_completed: this_completed_games,
+        review_score_averages,
     })
 }
 
@@ -250,9 +632,27 @@ pub fn get_logs(
 }
 
 #[tauri::command]
-pub fn delete_log(state: State<DatabaseConnections>, id: i32) -> Result<i32, Error> {
-    let conn = state.logs_conn.lock().unwrap();
-    conn.execute("DELETE FROM logs WHERE id = ?", [id])?;
+pub fn delete_log(
+    app_handle: tauri::AppHandle,
+    state: State<DatabaseConnections>,
+    id: i32,
+) -> Result<i32, Error> {
+    let mut conn = state.logs_conn.lock().unwrap();
+    let transaction = conn.transaction()?;
+    transaction.execute("DELETE FROM attachments WHERE log_id = ?", [id])?;
+    transaction.execute("DELETE FROM journal_entries WHERE log_id = ?", [id])?;
+    transaction.execute(
+        "DELETE FROM review_scores WHERE review_id IN (SELECT id FROM reviews WHERE log_id = ?)",
+        [id],
+    )?;
+    transaction.execute("DELETE FROM reviews WHERE log_id = ?", [id])?;
+    transaction.execute(
+        "UPDATE journal_entries SET session_log_id = NULL WHERE session_log_id = ?",
+        [id],
+    )?;
+    transaction.execute("DELETE FROM logs WHERE id = ?", [id])?;
+    transaction.commit()?;
+    remove_log_attachments(&app_handle, id)?;
     Ok(id)
 }
 
@@ -267,16 +667,20 @@ pub fn get_log_by_id(state: State<DatabaseConnections>, id: i32) -> Result<Log,
 #[tauri::command]
 pub fn add_log(state: State<DatabaseConnections>, log_data: LogData) -> Result<i32, Error> {
     let conn = state.logs_conn.lock().unwrap();
+    let (start_date, start_date_offset) = to_storage_timestamp(&log_data.start_date)?;
+    let (end_date, end_date_offset) = to_storage_timestamp(&log_data.end_date)?;
     conn.execute(
-        "INSERT INTO logs (game_id, start_date, rating, notes, status, minutes_played, end_date) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
-        [
-            log_data.game_id.to_string(),
-            log_data.start_date,
-            log_data.rating.to_string(),
+        "INSERT INTO logs (game_id, start_date, start_date_offset, rating, notes, status, minutes_played, end_date, end_date_offset) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            log_data.game_id,
+            start_date,
+            start_date_offset,
+            log_data.rating,
             log_data.notes,
             log_data.status,
-            log_data.minutes_played.to_string(),
-            log_data.end_date,
+            log_data.minutes_played,
+            end_date,
+            end_date_offset,
         ],
     )?;
     let id = conn.last_insert_rowid() as i32;
@@ -289,16 +693,20 @@ pub fn update_log(
     log_data: LogUpdateData,
 ) -> Result<i32, Error> {
     let conn = state.logs_conn.lock().unwrap();
+    let (start_date, start_date_offset) = to_storage_timestamp(&log_data.start_date)?;
+    let (end_date, end_date_offset) = to_storage_timestamp(&log_data.end_date)?;
     conn.execute(
-        "UPDATE logs SET start_date = ?1, end_date = ?7, rating = ?2, notes = ?3, status = ?4, minutes_played = ?5 WHERE id = ?6",
-        [
-            log_data.start_date,
-            log_data.rating.to_string(),
+        "UPDATE logs SET start_date = ?1, start_date_offset = ?2, end_date = ?3, end_date_offset = ?4, rating = ?5, notes = ?6, status = ?7, minutes_played = ?8, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = ?9",
+        params![
+            start_date,
+            start_date_offset,
+            end_date,
+            end_date_offset,
+            log_data.rating,
             log_data.notes,
             log_data.status,
-            log_data.minutes_played.to_string(),
-            log_data.id.to_string(),
-            log_data.end_date,
+            log_data.minutes_played,
+            log_data.id,
         ],
     )?;
     Ok(log_data.id)
@@ -0,0 +1,149 @@
+use std::{
+    cell::Cell,
+    fs::{self, File},
+    io::{BufReader, Read},
+    path::{Path, PathBuf},
+    rc::Rc,
+};
+
+use flate2::read::MultiGzDecoder;
+
+use crate::Error;
+
+const GZIP_MAGIC: [u8; 2] = [0x1f, 0x8b];
+const ZSTD_MAGIC: [u8; 4] = [0x28, 0xb5, 0x2f, 0xfd];
+
+/// How a dump file is compressed. Dumps are stored on disk as downloaded and only decompressed
+/// while they are read.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DumpCompression {
+    Gzip,
+    Zstd,
+}
+
+impl DumpCompression {
+    const ALL: [Option<DumpCompression>; 3] = [
+        None,
+        Some(DumpCompression::Gzip),
+        Some(DumpCompression::Zstd),
+    ];
+
+    fn extension(compression: Option<DumpCompression>) -> &'static str {
+        match compression {
+            None => "csv",
+            Some(DumpCompression::Gzip) => "csv.gz",
+            Some(DumpCompression::Zstd) => "csv.zst",
+        }
+    }
+
+    pub fn describe(compression: Option<DumpCompression>) -> &'static str {
+        match compression {
+            None => "plain CSV",
+            Some(DumpCompression::Gzip) => "gzip",
+            Some(DumpCompression::Zstd) => "zstd",
+        }
+    }
+
+    /// Reads the format from the file's magic bytes, `None` meaning plain CSV.
+    pub fn detect(path: &Path) -> Result<Option<DumpCompression>, Error> {
+        let mut magic = [0; 4];
+        let mut file = File::open(path)?;
+        let mut filled = 0;
+        while filled < magic.len() {
+            match file.read(&mut magic[filled..])? {
+                0 => break,
+                n => filled += n,
+            }
+        }
+        Ok(if magic[..filled].starts_with(&GZIP_MAGIC) {
+            Some(DumpCompression::Gzip)
+        } else if magic[..filled].starts_with(&ZSTD_MAGIC) {
+            Some(DumpCompression::Zstd)
+        } else {
+            None
+        })
+    }
+}
+
+pub fn dump_file_path(
+    directory: &Path,
+    table: &str,
+    compression: Option<DumpCompression>,
+) -> PathBuf {
+    directory.join(format!(
+        "{}.{}",
+        table,
+        DumpCompression::extension(compression)
+    ))
+}
+
+/// Finds the file holding a table's dump, whichever format it was stored in.
+pub fn find_dump_file(directory: &Path, table: &str) -> Option<PathBuf> {
+    DumpCompression::ALL
+        .into_iter()
+        .map(|compression| dump_file_path(directory, table, compression))
+        .find(|path| path.exists())
+}
+
+/// Removes a table's dump in every format, so a stale copy can't be picked up instead of a new one.
+pub fn remove_dump_files(directory: &Path, table: &str) -> Result<(), Error> {
+    for compression in DumpCompression::ALL {
+        let path = dump_file_path(directory, table, compression);
+        if path.exists() {
+            fs::remove_file(path)?;
+        }
+    }
+    Ok(())
+}
+
+struct CountingReader {
+    file: File,
+    bytes_read: Rc<Cell<u64>>,
+}
+
+impl Read for CountingReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let n = self.file.read(buf)?;
+        self.bytes_read.set(self.bytes_read.get() + n as u64);
+        Ok(n)
+    }
+}
+
+/// Streams the CSV text of a dump, decompressing on the fly.
+pub struct DumpReader {
+    inner: Box<dyn Read>,
+    bytes_read: Rc<Cell<u64>>,
+}
+
+impl DumpReader {
+    pub fn open(path: &Path) -> Result<Self, Error> {
+        let compression = DumpCompression::detect(path)?;
+        let bytes_read = Rc::new(Cell::new(0));
+        let file = CountingReader {
+            file: File::open(path)?,
+            bytes_read: bytes_read.clone(),
+        };
+        let inner: Box<dyn Read> = match compression {
+            None => Box::new(file),
+            Some(DumpCompression::Gzip) => Box::new(MultiGzDecoder::new(BufReader::new(file))),
+            Some(DumpCompression::Zstd) => Box::new(zstd::Decoder::new(file)?),
+        };
+        Ok(DumpReader { inner, bytes_read })
+    }
+
+    /// Bytes read from the file so far, which is what progress is measured against.
+    pub fn file_position(&self) -> u64 {
+        self.bytes_read.get()
+    }
+}
+
+impl Read for DumpReader {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+pub fn csv_reader(path: &Path) -> Result<csv::Reader<DumpReader>, Error> {
+    Ok(csv::Reader::from_reader(DumpReader::open(path)?))
+}
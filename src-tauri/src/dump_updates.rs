@@ -0,0 +1,222 @@
+use std::{fs, thread, time::Duration};
+
+use chrono::{DateTime, Utc};
+use tauri::Manager;
+use tauri_plugin_notification::{NotificationExt, PermissionState};
+
+use crate::{
+    dumps::{
+        download_dumps, get_all_dump_info, get_local_dump_versions, run_import,
+        save_local_dump_versions, DumpImportState, DumpImportSummary, RowErrorPolicy, TableChanges,
+    },
+    helpers::{create_dir_if_not_exists, get_app_data_directory, get_user_settings},
+    Error,
+};
+
+const SCHEDULE_POLL_INTERVAL: Duration = Duration::from_secs(10 * 60);
+const LAST_CHECK_FILE: &str = "last_dump_update_check";
+
+/// Checks for new dumps on the interval set in the user settings. Settings are re-read on every
+/// poll, so changes apply without restarting. The time of the last check is kept on disk so
+/// restarts don't reset the interval; on a fresh install the first check is left to the dumps
+/// page.
+pub fn schedule_dump_updates(app_handle: tauri::AppHandle) {
+    thread::spawn(move || {
+        if get_last_check(&app_handle).is_none() {
+            let _ = save_last_check(&app_handle, Utc::now());
+        }
+        loop {
+            thread::sleep(SCHEDULE_POLL_INTERVAL);
+            let settings = match get_user_settings(app_handle.clone()) {
+                Ok(settings) => settings.dump_updates,
+                Err(_) => continue,
+            };
+            let interval = chrono::Duration::hours(settings.interval_hours as i64);
+            let due = match get_last_check(&app_handle) {
+                Some(last_check) => Utc::now() - last_check >= interval,
+                None => true,
+            };
+            if !settings.automatic || !due {
+                continue;
+            }
+            if settings.pause_on_metered && is_connection_metered() {
+                continue;
+            }
+            if save_last_check(&app_handle, Utc::now()).is_err() {
+                continue;
+            }
+            let body = match tauri::async_runtime::block_on(update_dumps(
+                &app_handle,
+                settings.row_error_policy,
+            )) {
+                Ok(Some(summary)) => change_summary(&summary),
+                Ok(None) => continue,
+                Err(e) => format!("Could not update the game catalogue: {}", e),
+            };
+            notify(&app_handle, &body);
+        }
+    });
+}
+
+fn get_last_check(app_handle: &tauri::AppHandle) -> Option<DateTime<Utc>> {
+    let path = get_app_data_directory(app_handle)
+        .ok()?
+        .join(LAST_CHECK_FILE);
+    let last_check = fs::read_to_string(path).ok()?;
+    DateTime::parse_from_rfc3339(last_check.trim())
+        .ok()
+        .map(|last_check| last_check.with_timezone(&Utc))
+}
+
+fn save_last_check(app_handle: &tauri::AppHandle, checked_at: DateTime<Utc>) -> Result<(), Error> {
+    let path = get_app_data_directory(app_handle)?.join(LAST_CHECK_FILE);
+    fs::write(path, checked_at.to_rfc3339())?;
+    Ok(())
+}
+
+/// Downloads and imports only the dumps whose version changed. Returns `None` when everything is
+/// up to date or an import is already running. The downloads are kept after a failure so the next
+/// check can resume them.
+async fn update_dumps(
+    app_handle: &tauri::AppHandle,
+    policy: RowErrorPolicy,
+) -> Result<Option<DumpImportSummary>, Error> {
+    let mut local_versions = get_local_dump_versions(app_handle.clone())?;
+    let changed_dumps = get_all_dump_info(app_handle.clone())
+        .await?
+        .into_iter()
+        .filter(|info| local_versions.get(info.name) != Some(&info.version))
+        .collect::<Vec<_>>();
+    if changed_dumps.is_empty() {
+        return Ok(None);
+    }
+    let new_versions = changed_dumps
+        .iter()
+        .map(|info| (info.name.to_string(), info.version.clone()))
+        .collect::<Vec<_>>();
+    let import_state = app_handle.state::<DumpImportState>();
+    if !import_state.try_start() {
+        return Ok(None);
+    }
+    let directory = std::env::temp_dir().join("game-chronicle-updates");
+    let result = async {
+        create_dir_if_not_exists(&directory)?;
+        download_dumps(app_handle.clone(), changed_dumps, directory.clone()).await?;
+        run_import(app_handle, &directory, policy).map_err(|failure| Error::from(failure.message))
+    }
+    .await;
+    import_state.finish();
+    let summary = result?;
+    let _ = fs::remove_dir_all(&directory);
+    local_versions.extend(new_versions);
+    save_local_dump_versions(local_versions, app_handle.clone())?;
+    Ok(Some(summary))
+}
+
+fn change_summary(summary: &DumpImportSummary) -> String {
+    let tables = summary
+        .table_changes
+        .iter()
+        .filter_map(table_change_summary)
+        .collect::<Vec<_>>();
+    let mut body = match tables.is_empty() {
+        true => "Game catalogue updated, nothing changed.".to_string(),
+        false => format!("Game catalogue updated: {}.", tables.join("; ")),
+    };
+    if summary.skipped_rows > 0 {
+        body.push_str(&format!(" {} rows were skipped.", summary.skipped_rows));
+    }
+    for warning in &summary.warnings {
+        body.push_str(&format!(" {}.", warning));
+    }
+    body
+}
+
+fn table_change_summary(changes: &TableChanges) -> Option<String> {
+    let counts = [
+        (changes.added, "added"),
+        (changes.updated, "updated"),
+        (changes.removed, "removed"),
+    ]
+    .into_iter()
+    .filter(|(count, _)| *count > 0)
+    .map(|(count, change)| format!("{} {}", count, change))
+    .collect::<Vec<_>>();
+    if counts.is_empty() {
+        return None;
+    }
+    Some(format!(
+        "{} {}",
+        changes.table.replace('_', " "),
+        counts.join(", ")
+    ))
+}
+
+/// Asks for permission to notify if the user hasn't decided yet.
+pub fn request_notification_permission(app_handle: &tauri::AppHandle) {
+    if matches!(
+        app_handle.notification().permission_state(),
+        Ok(PermissionState::Prompt | PermissionState::PromptWithRationale)
+    ) {
+        let _ = app_handle.notification().request_permission();
+    }
+}
+
+fn notify(app_handle: &tauri::AppHandle, body: &str) {
+    if !matches!(
+        app_handle.notification().permission_state(),
+        Ok(PermissionState::Granted)
+    ) {
+        return;
+    }
+    let _ = app_handle
+        .notification()
+        .builder()
+        .title("Game Chronicle")
+        .body(body)
+        .show();
+}
+
+/// NetworkManager reports 1 (yes) or 3 (guessed yes) for metered connections.
+#[cfg(target_os = "linux")]
+fn is_connection_metered() -> bool {
+    std::process::Command::new("busctl")
+        .args([
+            "get-property",
+            "org.freedesktop.NetworkManager",
+            "/org/freedesktop/NetworkManager",
+            "org.freedesktop.NetworkManager",
+            "Metered",
+        ])
+        .output()
+        .map(|output| {
+            let metered = String::from_utf8_lossy(&output.stdout);
+            matches!(metered.trim(), "u 1" | "u 3")
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(target_os = "windows")]
+fn is_connection_metered() -> bool {
+    use std::os::windows::process::CommandExt;
+
+    const CREATE_NO_WINDOW: u32 = 0x08000000;
+    std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-Command",
+            "[Windows.Networking.Connectivity.NetworkInformation, Windows.Networking.Connectivity, ContentType = WindowsRuntime]::GetInternetConnectionProfile().GetConnectionCost().NetworkCostType",
+        ])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map(|output| {
+            let cost_type = String::from_utf8_lossy(&output.stdout);
+            matches!(cost_type.trim(), "Fixed" | "Variable")
+        })
+        .unwrap_or(false)
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "windows")))]
+fn is_connection_metered() -> bool {
+    false
+}
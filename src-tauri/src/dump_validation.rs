@@ -0,0 +1,225 @@
+use std::path::Path;
+
+use csv::Reader;
+use serde::de::DeserializeOwned;
+
+use crate::{
+    dump_compression::{csv_reader, find_dump_file, DumpReader},
+    dumps::{DeletedRow, RowErrorPolicy, DUMP_NAMES},
+    igdb::{
+        AlternativeName, Cover, Game, GameLocalization, GameReleaseDate, GameTimeToBeat,
+        InvolvedCompany, NamedEntity, Platform, PopularityPrimitive, Website,
+    },
+    Error,
+};
+
+const MAX_REPORTED_BAD_ROWS: usize = 20;
+
+/// Columns a dump must have and columns it may leave out. Any other column is reported as extra
+/// but doesn't stop the import.
+struct DumpSchema {
+    required: &'static [&'static str],
+    optional: &'static [&'static str],
+}
+
+type RowCheck = fn(&mut Reader<DumpReader>, &mut DumpValidation);
+
+#[derive(Debug, serde::Serialize, Clone)]
+pub struct DumpBadRow {
+    row: u64,
+    message: String,
+}
+
+#[derive(Debug, Default, serde::Serialize, Clone)]
+pub struct DumpValidation {
+    table: String,
+    missing_columns: Vec<String>,
+    extra_columns: Vec<String>,
+    bad_row_count: u64,
+    bad_rows: Vec<DumpBadRow>,
+}
+
+impl DumpValidation {
+    /// Missing columns always stop the import; bad rows only when they wouldn't be skipped anyway.
+    pub fn is_fatal(&self, policy: RowErrorPolicy) -> bool {
+        !self.missing_columns.is_empty()
+            || (matches!(policy, RowErrorPolicy::Abort) && self.bad_row_count > 0)
+    }
+
+    fn has_problems(&self) -> bool {
+        !self.missing_columns.is_empty() || !self.extra_columns.is_empty() || self.bad_row_count > 0
+    }
+}
+
+fn dump_schema(name: &str) -> Option<(DumpSchema, RowCheck)> {
+    if name.ends_with("_deleted") {
+        return Some((
+            DumpSchema {
+                required: &["id"],
+                optional: &["merged_into"],
+            },
+            check_rows::<DeletedRow>,
+        ));
+    }
+    let named_entity = DumpSchema {
+        required: &["id", "name"],
+        optional: &[],
+    };
+    let schema: (DumpSchema, RowCheck) = match name {
+        "covers" => (
+            DumpSchema {
+                required: &["id", "image_id"],
+                optional: &[],
+            },
+            check_rows::<Cover>,
+        ),
+        "websites" => (
+            DumpSchema {
+                required: &["id", "url"],
+                optional: &[],
+            },
+            check_rows::<Website>,
+        ),
+        "platforms" => (
+            DumpSchema {
+                required: &["id", "name"],
+                optional: &["category"],
+            },
+            check_rows::<Platform>,
+        ),
+        "genres" | "themes" | "game_modes" | "franchises" | "collections" | "companies" => {
+            (named_entity, check_rows::<NamedEntity>)
+        }
+        "games" => (
+            DumpSchema {
+                required: &[
+                    "id",
+                    "name",
+                    "category",
+                    "websites",
+                    "similar_games",
+                    "platforms",
+                ],
+                optional: &[
+                    "cover",
+                    "version_parent",
+                    "total_rating",
+                    "genres",
+                    "themes",
+                    "game_modes",
+                    "franchises",
+                    "collections",
+                    "summary",
+                    "storyline",
+                ],
+            },
+            check_rows::<Game>,
+        ),
+        "involved_companies" => (
+            DumpSchema {
+                required: &["id", "game", "company", "developer", "publisher"],
+                optional: &[],
+            },
+            check_rows::<InvolvedCompany>,
+        ),
+        "release_dates" => (
+            DumpSchema {
+                required: &["id", "game"],
+                optional: &["platform", "date", "region"],
+            },
+            check_rows::<GameReleaseDate>,
+        ),
+        "alternative_names" => (
+            DumpSchema {
+                required: &["id", "game", "name"],
+                optional: &["comment"],
+            },
+            check_rows::<AlternativeName>,
+        ),
+        "game_localizations" => (
+            DumpSchema {
+                required: &["id", "game", "name"],
+                optional: &["region"],
+            },
+            check_rows::<GameLocalization>,
+        ),
+        "popularity_primitives" => (
+            DumpSchema {
+                required: &["id", "game_id", "popularity_type", "value"],
+                optional: &[],
+            },
+            check_rows::<PopularityPrimitive>,
+        ),
+        "game_time_to_beats" => (
+            DumpSchema {
+                required: &["id", "game_id"],
+                optional: &["hastily", "normally", "completely"],
+            },
+            check_rows::<GameTimeToBeat>,
+        ),
+        _ => return None,
+    };
+    Some(schema)
+}
+
+fn check_rows<T: DeserializeOwned>(
+    reader: &mut Reader<DumpReader>,
+    validation: &mut DumpValidation,
+) {
+    for (index, record) in reader.deserialize::<T>().enumerate() {
+        if let Err(e) = record {
+            validation.bad_row_count += 1;
+            if validation.bad_rows.len() < MAX_REPORTED_BAD_ROWS {
+                validation.bad_rows.push(DumpBadRow {
+                    row: index as u64 + 1,
+                    message: e.to_string(),
+                });
+            }
+        }
+    }
+}
+
+fn validate_csv(path: &Path, table: &str) -> Result<Option<DumpValidation>, Error> {
+    let Some((schema, check)) = dump_schema(table) else {
+        return Ok(None);
+    };
+    let mut reader = csv_reader(path)?;
+    let headers = reader.headers()?.clone();
+    let mut validation = DumpValidation {
+        table: table.to_string(),
+        ..Default::default()
+    };
+    validation.missing_columns = schema
+        .required
+        .iter()
+        .filter(|column| !headers.iter().any(|header| header == **column))
+        .map(|column| column.to_string())
+        .collect();
+    validation.extra_columns = headers
+        .iter()
+        .filter(|header| !schema.required.contains(header) && !schema.optional.contains(header))
+        .map(|header| header.to_string())
+        .collect();
+    // Every row would fail to deserialize, listing them adds nothing.
+    if validation.missing_columns.is_empty() {
+        check(&mut reader, &mut validation);
+    }
+    Ok(Some(validation).filter(DumpValidation::has_problems))
+}
+
+/// Checks the headers and every row of the dumps in a directory against the shape the importer
+/// expects, without touching the database. Only dumps with problems are reported.
+pub fn validate_dumps(from_directory: &Path) -> Result<Vec<DumpValidation>, Error> {
+    let mut report = vec![];
+    for name in DUMP_NAMES {
+        for table in [name.to_string(), format!("{}_deleted", name)] {
+            let Some(path) = find_dump_file(from_directory, &table) else {
+                continue;
+            };
+            if let Some(validation) = validate_csv(&path, &table)? {
+                report.push(validation);
+            }
+        }
+    }
+    Ok(report)
+}
@@ -1,64 +1,217 @@
 use std::{
-    collections::HashMap,
-    fs::{self, File},
-    io::Read,
-    path::PathBuf,
+    collections::{HashMap, HashSet},
+    fs::{self, File, OpenOptions},
+    io::{BufWriter, Read, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicBool, Ordering},
     thread,
 };
 
-use csv::Reader;
+use reqwest::{
+    header::{CONTENT_RANGE, ETAG, IF_RANGE, LAST_MODIFIED, RANGE},
+    StatusCode,
+};
 use rusqlite::{params, Connection, OptionalExtension, Transaction};
-use tauri::Emitter;
+use sha2::{Digest, Sha256};
+use tauri::{Emitter, Manager, State};
 
 use crate::{
-    helpers::get_app_data_directory,
-    igdb::{Cover, Game, Platform, PopularityPrimitive, Website},
-    Error,
+    database::{
+        create_igdb_staging, drop_search_triggers, has_previous_igdb_database,
+        rebuild_search_indexes, rollback_igdb_database, swap_igdb_database, verify_igdb_database,
+    },
+    dump_compression::{
+        csv_reader, dump_file_path, find_dump_file, remove_dump_files, DumpCompression,
+    },
+    dump_validation::{validate_dumps, DumpValidation},
+    helpers::{get_app_data_directory, get_user_settings, remove_file_if_exists},
+    igdb::{
+        AlternativeName, Cover, Game, GameLocalization, GameReleaseDate, GameTimeToBeat,
+        InvolvedCompany, NamedEntity, Platform, PopularityPrimitive, Website,
+    },
+    DatabaseConnections, Error,
 };
 
 #[derive(serde::Deserialize, Debug, serde::Serialize)]
 pub struct CsvUrlResponse {
     pub url: String,
     pub version: String,
+    pub checksum: Option<String>,
+    pub delta_since: Option<String>,
+    pub deleted_url: Option<String>,
+    pub deleted_checksum: Option<String>,
+    #[serde(default)]
+    pub compression: Option<DumpCompression>,
 }
 
+/// When `delta_since` is set, `url` only holds rows changed since that version and `deleted_url`
+/// lists the ids removed since then. `compression` applies to both files; when it isn't given the
+/// format is detected from the downloaded bytes.
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct DumpInfo<'a> {
     pub name: &'a str,
     pub url: String,
     pub version: String,
+    pub checksum: Option<String>,
+    pub delta_since: Option<String>,
+    pub deleted_url: Option<String>,
+    pub deleted_checksum: Option<String>,
+    #[serde(default)]
+    pub compression: Option<DumpCompression>,
+}
+
+/// `merged_into` is only set for games that IGDB merged into another game.
+#[derive(Debug, serde::Deserialize)]
+pub struct DeletedRow {
+    id: i32,
+    #[serde(default)]
+    merged_into: Option<i32>,
 }
 
 type DumpVersions = HashMap<String, String>;
 
-async fn get_csv_url(endpoint: &str) -> Result<CsvUrlResponse, Error> {
-    let client = reqwest::Client::new();
-    let response = client
-        .get(format!("https://api.gamechronicle.app/csv/{}", endpoint))
-        .send()
-        .await?
-        .json::<CsvUrlResponse>()
-        .await?;
-    Ok(response)
+pub const DEFAULT_DUMP_SOURCE: &str = "https://api.gamechronicle.app/csv/";
+const DUMP_MANIFEST_FILE: &str = "manifest.json";
+pub const DUMP_NAMES: [&str; 16] = [
+    "covers",
+    "websites",
+    "platforms",
+    "genres",
+    "themes",
+    "game_modes",
+    "franchises",
+    "collections",
+    "companies",
+    "games",
+    "involved_companies",
+    "release_dates",
+    "alternative_names",
+    "game_localizations",
+    "popularity_primitives",
+    "game_time_to_beats",
+];
+/// Link table, link column and linked table for the id lists in the games dump.
+const GAME_LINK_TABLES: [(&str, &str, &str); 8] = [
+    ("game_websites", "website_id", "websites"),
+    ("game_platforms", "platform_id", "platforms"),
+    ("similar_games", "similar_game_id", "games"),
+    ("game_genres", "genre_id", "genres"),
+    ("game_themes", "theme_id", "themes"),
+    ("game_game_modes", "game_mode_id", "game_modes"),
+    ("game_franchises", "franchise_id", "franchises"),
+    ("game_collections", "collection_id", "collections"),
+];
+
+/// Where dumps are fetched from: an HTTP API serving `{base}/{endpoint}` like the official one, or
+/// a local directory holding the CSVs next to a manifest.
+enum DumpSource {
+    Http(String),
+    Local(PathBuf),
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct ManifestEntry {
+    version: String,
+    file: Option<String>,
+    checksum: Option<String>,
+    compression: Option<DumpCompression>,
+    #[serde(default)]
+    deltas: HashMap<String, ManifestDelta>,
+}
+
+/// A delta without its own `compression` uses the one of its entry.
+#[derive(Debug, serde::Deserialize)]
+struct ManifestDelta {
+    file: String,
+    checksum: Option<String>,
+    deleted_file: Option<String>,
+    deleted_checksum: Option<String>,
+    compression: Option<DumpCompression>,
+}
+
+fn parse_dump_source(source: &str) -> Result<DumpSource, Error> {
+    let source = source.trim();
+    if source.starts_with("http://") || source.starts_with("https://") {
+        return Ok(DumpSource::Http(source.trim_end_matches('/').to_string()));
+    }
+    let directory = match source.strip_prefix("file://") {
+        Some(_) => reqwest::Url::parse(source)
+            .ok()
+            .and_then(|url| url.to_file_path().ok())
+            .ok_or_else(|| Error::from(format!("'{}' is not a valid file URL", source)))?,
+        None => PathBuf::from(source),
+    };
+    if !directory.is_dir() {
+        return Err(Error::from(format!(
+            "Dump source '{}' is not a directory",
+            directory.display()
+        )));
+    }
+    Ok(DumpSource::Local(directory))
 }
 
-async fn get_csv_data(url: &str) -> Result<String, Error> {
+async fn get_csv_url(
+    base_url: &str,
+    endpoint: &str,
+    since: Option<&str>,
+) -> Result<CsvUrlResponse, Error> {
     let client = reqwest::Client::new();
-    let response = client.get(url).send().await?.text().await?;
+    let mut request = client.get(format!("{}/{}", base_url, endpoint));
+    if let Some(since) = since {
+        request = request.query(&[("since", since)]);
+    }
+    let response = request.send().await?.json::<CsvUrlResponse>().await?;
     Ok(response)
 }
 
-fn parse_csv<T>(csv_path: &std::path::PathBuf) -> Result<Vec<T>, Error>
-where
-    T: serde::de::DeserializeOwned + std::fmt::Debug,
-{
-    let mut rdr = Reader::from_path(csv_path)?;
-    let mut items = vec![];
-    for result in rdr.deserialize() {
-        let item: T = result?;
-        items.push(item);
+fn file_url(directory: &Path, file: &str) -> Result<String, Error> {
+    let file_path = directory.join(file);
+    let url = reqwest::Url::from_file_path(&file_path)
+        .map_err(|_| Error::from(format!("'{}' is not an absolute path", file_path.display())))?;
+    Ok(url.to_string())
+}
+
+fn get_local_csv_urls(
+    directory: &Path,
+    local_versions: &DumpVersions,
+) -> Result<HashMap<String, CsvUrlResponse>, Error> {
+    let manifest_path = directory.join(DUMP_MANIFEST_FILE);
+    let manifest: HashMap<String, ManifestEntry> =
+        serde_json::from_str(&fs::read_to_string(&manifest_path)?)?;
+    let mut responses = HashMap::new();
+    for (name, mut entry) in manifest {
+        let delta = local_versions
+            .get(&name)
+            .and_then(|since| entry.deltas.remove_entry(since));
+        let response = match delta {
+            Some((since, delta)) => CsvUrlResponse {
+                url: file_url(directory, &delta.file)?,
+                version: entry.version,
+                checksum: delta.checksum,
+                delta_since: Some(since),
+                deleted_url: match &delta.deleted_file {
+                    Some(file) => Some(file_url(directory, file)?),
+                    None => None,
+                },
+                deleted_checksum: delta.deleted_checksum,
+                compression: delta.compression.or(entry.compression),
+            },
+            None => CsvUrlResponse {
+                url: file_url(
+                    directory,
+                    &entry.file.unwrap_or_else(|| format!("{}.csv", name)),
+                )?,
+                version: entry.version,
+                checksum: entry.checksum,
+                delta_since: None,
+                deleted_url: None,
+                deleted_checksum: None,
+                compression: entry.compression,
+            },
+        };
+        responses.insert(name, response);
     }
-    Ok(items)
+    Ok(responses)
 }
 
 #[tauri::command]
@@ -68,11 +221,9 @@ pub fn get_local_dump_versions(app_handle: tauri::AppHandle) -> Result<DumpVersi
         Ok(file) => file,
         Err(_) => {
             let mut hm = DumpVersions::new();
-            hm.insert("covers".to_string(), "".to_string());
-            hm.insert("websites".to_string(), "".to_string());
-            hm.insert("platforms".to_string(), "".to_string());
-            hm.insert("games".to_string(), "".to_string());
-            hm.insert("popularity_primitives".to_string(), "".to_string());
+            for name in DUMP_NAMES {
+                hm.insert(name.to_string(), "".to_string());
+            }
             return Ok(hm);
         }
     };
@@ -88,394 +239,1660 @@ pub fn get_local_dump_versions(app_handle: tauri::AppHandle) -> Result<DumpVersi
 }
 
 #[tauri::command]
-pub async fn get_all_dump_info() -> Result<Vec<DumpInfo<'static>>, Error> {
-    let endpoints = [
-        "covers",
-        "websites",
-        "platforms",
-        "games",
-        "popularity_primitives",
-    ];
+pub async fn get_all_dump_info(
+    app_handle: tauri::AppHandle,
+) -> Result<Vec<DumpInfo<'static>>, Error> {
+    let dump_source = parse_dump_source(&get_user_settings(app_handle.clone())?.dump_source)?;
+    let local_versions = get_local_dump_versions(app_handle)?;
+    let mut local_csv_urls = match &dump_source {
+        DumpSource::Local(directory) => get_local_csv_urls(directory, &local_versions)?,
+        DumpSource::Http(_) => HashMap::new(),
+    };
     let mut dumps_info: Vec<DumpInfo> = vec![];
-    for endpoint in endpoints {
-        let csv_response = get_csv_url(endpoint).await?;
+    for endpoint in DUMP_NAMES {
+        // Deltas can only be applied on top of a complete local copy of the same table.
+        let since = local_versions
+            .get(endpoint)
+            .map(|version| version.as_str())
+            .filter(|version| !version.is_empty());
+        let csv_response = match &dump_source {
+            DumpSource::Http(base_url) => get_csv_url(base_url, endpoint, since).await?,
+            DumpSource::Local(directory) => match local_csv_urls.remove(endpoint) {
+                Some(csv_response) => csv_response,
+                None => Err(Error::from(format!(
+                    "{} has no entry for {}",
+                    directory.join(DUMP_MANIFEST_FILE).display(),
+                    endpoint
+                )))?,
+            },
+        };
+        if csv_response.delta_since.is_some() && csv_response.delta_since.as_deref() != since {
+            return Err(Error::from(format!(
+                "Received a {} delta from version {}, but the local version is {}",
+                endpoint,
+                csv_response.delta_since.unwrap_or_default(),
+                since.unwrap_or("missing")
+            )));
+        }
         dumps_info.push(DumpInfo {
             name: endpoint,
             url: csv_response.url,
             version: csv_response.version,
+            checksum: csv_response.checksum,
+            delta_since: csv_response.delta_since,
+            deleted_url: csv_response.deleted_url,
+            deleted_checksum: csv_response.deleted_checksum,
+            compression: csv_response.compression,
         });
     }
     Ok(dumps_info)
 }
 
+#[derive(Debug, serde::Serialize, Clone)]
+struct DumpProgressPayload<'a> {
+    name: &'a str,
+    stage: &'a str,
+    bytes_processed: u64,
+    total_bytes: Option<u64>,
+    rows_processed: Option<u64>,
+}
+
+const PROGRESS_INTERVAL_BYTES: u64 = 1024 * 1024;
+const IMPORT_BATCH_SIZE: usize = 5000;
+
+const MAX_DOWNLOAD_ATTEMPTS: u32 = 3;
+
+fn content_range_total(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .rsplit('/')
+        .next()?
+        .parse()
+        .ok()
+}
+
+fn file_sha256(path: &Path) -> Result<String, Error> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(format!("{:x}", hasher.finalize()))
+}
+
+type ReportProgress<'a> = &'a (dyn Fn(DumpProgressPayload) -> Result<(), Error> + Sync);
+
+/// If-Range only accepts a strong ETag or a date.
+fn resume_validator(response: &reqwest::Response) -> Option<String> {
+    let headers = response.headers();
+    headers
+        .get(ETAG)
+        .filter(|etag| !etag.as_bytes().starts_with(b"W/"))
+        .or_else(|| headers.get(LAST_MODIFIED))?
+        .to_str()
+        .ok()
+        .map(String::from)
+}
+
+fn content_range_start(response: &reqwest::Response) -> Option<u64> {
+    response
+        .headers()
+        .get(CONTENT_RANGE)?
+        .to_str()
+        .ok()?
+        .strip_prefix("bytes ")?
+        .split('-')
+        .next()?
+        .parse()
+        .ok()
+}
+
+async fn download_to_partial(
+    report_progress: ReportProgress<'_>,
+    name: &str,
+    url: &str,
+    partial_path: &Path,
+) -> Result<(), Error> {
+    let validator_path = partial_path.with_extension("validator");
+    let existing_bytes = match fs::metadata(partial_path) {
+        Ok(metadata) => metadata.len(),
+        Err(_) => 0,
+    };
+    let client = reqwest::Client::new();
+    let mut request = client.get(url);
+    if existing_bytes > 0 {
+        request = request.header(RANGE, format!("bytes={}-", existing_bytes));
+        if let Ok(validator) = fs::read_to_string(&validator_path) {
+            request = request.header(IF_RANGE, validator);
+        }
+    }
+    let response = request.send().await?;
+    if existing_bytes > 0 && response.status() == StatusCode::RANGE_NOT_SATISFIABLE {
+        if content_range_total(&response) == Some(existing_bytes) {
+            return Ok(());
+        }
+        remove_file_if_exists(partial_path)?;
+        return Err(Error::from(format!(
+            "Could not resume the {} dump download",
+            name
+        )));
+    }
+    let mut response = response.error_for_status()?;
+    let resuming = response.status() == StatusCode::PARTIAL_CONTENT;
+    if resuming && content_range_start(&response) != Some(existing_bytes) {
+        remove_file_if_exists(partial_path)?;
+        return Err(Error::from(format!(
+            "Server resumed the {} dump download at the wrong position",
+            name
+        )));
+    }
+    let (file, mut bytes_downloaded, total_bytes) = match resuming {
+        true => (
+            OpenOptions::new().append(true).open(partial_path)?,
+            existing_bytes,
+            content_range_total(&response),
+        ),
+        false => {
+            match resume_validator(&response) {
+                Some(validator) => fs::write(&validator_path, validator)?,
+                None => remove_file_if_exists(&validator_path)?,
+            }
+            (File::create(partial_path)?, 0, response.content_length())
+        }
+    };
+    let mut csv_file = BufWriter::new(file);
+    let mut last_reported: u64 = bytes_downloaded;
+    while let Some(chunk) = response.chunk().await? {
+        csv_file.write_all(&chunk)?;
+        bytes_downloaded += chunk.len() as u64;
+        if bytes_downloaded - last_reported >= PROGRESS_INTERVAL_BYTES {
+            last_reported = bytes_downloaded;
+            report_progress(DumpProgressPayload {
+                name,
+                stage: "download",
+                bytes_processed: bytes_downloaded,
+                total_bytes,
+                rows_processed: None,
+            })?;
+        }
+    }
+    csv_file.flush()?;
+    if let Some(total_bytes) = total_bytes {
+        if bytes_downloaded != total_bytes {
+            return Err(Error::from(format!(
+                "Download of {} dump ended after {} of {} bytes",
+                name, bytes_downloaded, total_bytes
+            )));
+        }
+    }
+    report_progress(DumpProgressPayload {
+        name,
+        stage: "download",
+        bytes_processed: bytes_downloaded,
+        total_bytes,
+        rows_processed: None,
+    })?;
+    Ok(())
+}
+
+/// Partial downloads are named after the file they belong to, so a leftover from another version
+/// of a dump is never resumed.
+fn partial_download_path(
+    to_directory: &Path,
+    table: &str,
+    url: &str,
+    version: &str,
+    checksum: Option<&str>,
+) -> PathBuf {
+    let mut hasher = Sha256::new();
+    for part in [url, version, checksum.unwrap_or_default()] {
+        hasher.update(part.as_bytes());
+        hasher.update([0]);
+    }
+    let key = format!("{:x}", hasher.finalize());
+    to_directory.join(format!("{}.{}.part", table, &key[..16]))
+}
+
+fn remove_partial_downloads(to_directory: &Path, table: &str) -> Result<(), Error> {
+    let prefix = format!("{}.", table);
+    for entry in fs::read_dir(to_directory)? {
+        let path = entry?.path();
+        let is_partial = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_prefix(&prefix))
+            .is_some_and(|rest| {
+                rest == "part" || rest.ends_with(".part") || rest.ends_with(".validator")
+            });
+        if is_partial {
+            remove_file_if_exists(&path)?;
+        }
+    }
+    Ok(())
+}
+
+async fn download_csv(
+    report_progress: ReportProgress<'_>,
+    info: &DumpInfo<'_>,
+    url: &str,
+    checksum: Option<&str>,
+    to_directory: &Path,
+    table: &str,
+) -> Result<(), Error> {
+    let (name, compression) = (info.name, info.compression);
+    let partial_path = partial_download_path(to_directory, table, url, &info.version, checksum);
+    if !partial_path.exists() {
+        remove_partial_downloads(to_directory, table)?;
+    }
+    let local_path = reqwest::Url::parse(url)
+        .ok()
+        .filter(|url| url.scheme() == "file")
+        .and_then(|url| url.to_file_path().ok());
+    match local_path {
+        Some(local_path) => {
+            let bytes_copied = fs::copy(&local_path, &partial_path)?;
+            report_progress(DumpProgressPayload {
+                name,
+                stage: "download",
+                bytes_processed: bytes_copied,
+                total_bytes: Some(bytes_copied),
+                rows_processed: None,
+            })?;
+        }
+        None => {
+            let mut attempt = 1;
+            while let Err(e) = download_to_partial(report_progress, name, url, &partial_path).await
+            {
+                if attempt >= MAX_DOWNLOAD_ATTEMPTS {
+                    return Err(e);
+                }
+                attempt += 1;
+            }
+        }
+    }
+    if let Some(expected_checksum) = checksum {
+        let checksum = file_sha256(&partial_path)?;
+        if !checksum.eq_ignore_ascii_case(expected_checksum) {
+            remove_partial_downloads(to_directory, table)?;
+            return Err(Error::from(format!(
+                "Checksum mismatch for {} dump: expected {}, got {}",
+                name, expected_checksum, checksum
+            )));
+        }
+    }
+    let detected = DumpCompression::detect(&partial_path)?;
+    if compression.is_some() && compression != detected {
+        remove_partial_downloads(to_directory, table)?;
+        return Err(Error::from(format!(
+            "{} dump was announced as {} but is {}",
+            name,
+            DumpCompression::describe(compression),
+            DumpCompression::describe(detected)
+        )));
+    }
+    remove_dump_files(to_directory, table)?;
+    fs::rename(&partial_path, dump_file_path(to_directory, table, detected))?;
+    remove_partial_downloads(to_directory, table)?;
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn download_dumps(
+    app_handle: tauri::AppHandle,
     dump_info: Vec<DumpInfo<'_>>,
     to_directory: PathBuf,
 ) -> Result<(), Error> {
+    let report_progress = |payload: DumpProgressPayload<'_>| -> Result<(), Error> {
+        Ok(app_handle.emit("dump_progress", payload)?)
+    };
     for info in dump_info {
-        let csv_data = get_csv_data(&info.url).await?;
-        let mut csv_file = File::create(to_directory.join(format!("{}.csv", info.name)))?;
-        std::io::copy(&mut csv_data.as_bytes(), &mut csv_file)?;
+        download_csv(
+            &report_progress,
+            &info,
+            &info.url,
+            info.checksum.as_deref(),
+            &to_directory,
+            info.name,
+        )
+        .await?;
+        let deleted_table = format!("{}_deleted", info.name);
+        match &info.deleted_url {
+            Some(deleted_url) => {
+                download_csv(
+                    &report_progress,
+                    &info,
+                    deleted_url,
+                    info.deleted_checksum.as_deref(),
+                    &to_directory,
+                    &deleted_table,
+                )
+                .await?
+            }
+            None if info.delta_since.is_some() => {
+                remove_dump_files(&to_directory, &deleted_table)?;
+                fs::write(
+                    dump_file_path(&to_directory, &deleted_table, None),
+                    "id,merged_into\n",
+                )?
+            }
+            None => remove_dump_files(&to_directory, &deleted_table)?,
+        }
     }
     Ok(())
 }
 
+#[derive(Debug, Clone, Copy, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RowErrorPolicy {
+    Skip,
+    Abort,
+}
+
+#[derive(Debug, serde::Serialize, Clone)]
+pub struct DumpRowError {
+    table: String,
+    row: u64,
+    message: String,
+}
+
+#[derive(Debug, Default, serde::Serialize, Clone)]
+pub struct DumpImportSummary {
+    pub imported_rows: Vec<(String, u64)>,
+    pub table_changes: Vec<TableChanges>,
+    pub skipped_rows: u64,
+    row_errors: Vec<DumpRowError>,
+    pub removed_games: Vec<RemovedGame>,
+    pub remapped_games: Vec<GameRemap>,
+    pub unresolved_game_ids: Vec<i32>,
+    /// Problems that came up after the new catalogue was already in place.
+    pub warnings: Vec<String>,
+}
+
+impl DumpImportSummary {
+    fn changes_to(&mut self, table: &str) -> &mut TableChanges {
+        let index = match self
+            .table_changes
+            .iter()
+            .position(|changes| changes.table == table)
+        {
+            Some(index) => index,
+            None => {
+                self.table_changes.push(TableChanges {
+                    table: table.to_string(),
+                    ..Default::default()
+                });
+                self.table_changes.len() - 1
+            }
+        };
+        &mut self.table_changes[index]
+    }
+}
+
+#[derive(Debug, Default, serde::Serialize, Clone)]
+pub struct TableChanges {
+    pub table: String,
+    pub added: u64,
+    pub updated: u64,
+    pub removed: u64,
+}
+
+#[derive(Debug, serde::Serialize, Clone)]
+pub struct RemovedGame {
+    game_id: i32,
+    name: Option<String>,
+    merged_into: Option<i32>,
+}
+
+/// Logs and executables that pointed at a merged game and now point at its replacement.
+#[derive(Debug, serde::Serialize, Clone)]
+pub struct GameRemap {
+    from_game_id: i32,
+    to_game_id: i32,
+    logs: usize,
+    executables: usize,
+}
+
+#[derive(Debug, serde::Serialize, Clone)]
+pub struct DumpImportFailure {
+    table: Option<String>,
+    row: Option<u64>,
+    pub message: String,
+    cancelled: bool,
+    validation: Vec<DumpValidation>,
+}
+
+impl DumpImportFailure {
+    fn new(table: Option<&str>, row: Option<u64>, error: Error) -> Self {
+        DumpImportFailure {
+            table: table.map(|table| table.to_string()),
+            row,
+            message: error.to_string(),
+            cancelled: false,
+            validation: vec![],
+        }
+    }
+
+    fn cancelled(table: &str, row: u64) -> Self {
+        DumpImportFailure {
+            table: Some(table.to_string()),
+            row: Some(row),
+            message: "Import cancelled".to_string(),
+            cancelled: true,
+            validation: vec![],
+        }
+    }
+
+    fn invalid(validation: Vec<DumpValidation>) -> Self {
+        DumpImportFailure {
+            table: None,
+            row: None,
+            message: "The dump files don't match the expected format, nothing was imported"
+                .to_string(),
+            cancelled: false,
+            validation,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct DumpImportState {
+    cancelled: AtomicBool,
+    running: AtomicBool,
+}
+
+impl DumpImportState {
+    /// Claims the importer, returning false when another import already holds it.
+    pub fn try_start(&self) -> bool {
+        self.cancelled.store(false, Ordering::Relaxed);
+        !self.running.swap(true, Ordering::SeqCst)
+    }
+
+    pub fn finish(&self) {
+        self.running.store(false, Ordering::SeqCst);
+    }
+}
+
+const MAX_REPORTED_ROW_ERRORS: usize = 100;
+
+struct ImportContext<'a> {
+    cancelled: &'a AtomicBool,
+    report_progress: &'a dyn Fn(DumpProgressPayload) -> Result<(), Error>,
+}
+
+/// Returns how many rows of the dump's table were written or deleted.
+type InsertFunc<T> = fn(&mut Transaction, &Vec<T>) -> Result<usize, Error>;
+
+fn with_savepoint<F, R>(transaction: &mut Transaction, f: F) -> Result<R, Error>
+where
+    F: FnOnce(&mut Transaction) -> Result<R, Error>,
+{
+    transaction.execute_batch("SAVEPOINT dump_batch")?;
+    match f(transaction) {
+        Ok(result) => {
+            transaction.execute_batch("RELEASE dump_batch")?;
+            Ok(result)
+        }
+        Err(e) => {
+            transaction.execute_batch("ROLLBACK TO dump_batch; RELEASE dump_batch")?;
+            Err(e)
+        }
+    }
+}
+
+fn handle_row_error(
+    name: &str,
+    row: u64,
+    error: Error,
+    policy: RowErrorPolicy,
+    summary: &mut DumpImportSummary,
+) -> Result<(), DumpImportFailure> {
+    match policy {
+        RowErrorPolicy::Abort => Err(DumpImportFailure::new(Some(name), Some(row), error)),
+        RowErrorPolicy::Skip => {
+            summary.skipped_rows += 1;
+            if summary.row_errors.len() < MAX_REPORTED_ROW_ERRORS {
+                summary.row_errors.push(DumpRowError {
+                    table: name.to_string(),
+                    row,
+                    message: error.to_string(),
+                });
+            }
+            Ok(())
+        }
+    }
+}
+
+fn insert_batch<T>(
+    name: &str,
+    insert_func: InsertFunc<T>,
+    transaction: &mut Transaction,
+    batch: Vec<T>,
+    batch_rows: &[u64],
+    policy: RowErrorPolicy,
+    summary: &mut DumpImportSummary,
+) -> Result<usize, DumpImportFailure> {
+    if let Ok(written) = with_savepoint(transaction, |transaction| insert_func(transaction, &batch))
+    {
+        return Ok(written);
+    }
+    let mut written = 0;
+    for (record, row) in batch.into_iter().zip(batch_rows) {
+        let single = vec![record];
+        match with_savepoint(transaction, |transaction| insert_func(transaction, &single)) {
+            Ok(single_written) => written += single_written,
+            Err(e) => handle_row_error(name, *row, e, policy, summary)?,
+        }
+    }
+    Ok(written)
+}
+
 fn import_csv<T: serde::de::DeserializeOwned>(
-    file_path: PathBuf,
-    parse_func: fn(&PathBuf) -> Result<Vec<T>, Error>,
-    insert_func: fn(&mut Transaction, &Vec<T>) -> Result<(), Error>,
+    context: &ImportContext,
+    from_directory: &Path,
+    name: &str,
+    insert_func: InsertFunc<T>,
     transaction: &mut rusqlite::Transaction,
+    policy: RowErrorPolicy,
+    summary: &mut DumpImportSummary,
+) -> Result<(), DumpImportFailure> {
+    let failure = |row: Option<u64>, e: Error| DumpImportFailure::new(Some(name), row, e);
+    let Some(file_path) = find_dump_file(from_directory, name) else {
+        return Ok(());
+    };
+    let total_bytes = fs::metadata(&file_path)
+        .map_err(|e| failure(None, e.into()))?
+        .len();
+    transaction
+        .busy_timeout(std::time::Duration::from_secs(10))
+        .map_err(|e| failure(None, e.into()))?;
+    let table = name.strip_suffix("_deleted").unwrap_or(name);
+    let rows_before = count_rows(transaction, table).map_err(|e| failure(None, e))?;
+    let mut written: usize = 0;
+    let mut rdr = csv_reader(&file_path).map_err(|e| failure(None, e))?;
+    let mut records = rdr.deserialize::<T>();
+    let mut batch: Vec<T> = Vec::with_capacity(IMPORT_BATCH_SIZE);
+    let mut batch_rows: Vec<u64> = Vec::with_capacity(IMPORT_BATCH_SIZE);
+    let mut row: u64 = 0;
+    loop {
+        if context.cancelled.load(Ordering::Relaxed) {
+            return Err(DumpImportFailure::cancelled(name, row));
+        }
+        let record = records.next();
+        let finished = record.is_none();
+        if let Some(record) = record {
+            row += 1;
+            match record {
+                Ok(record) => {
+                    batch.push(record);
+                    batch_rows.push(row);
+                }
+                Err(e) => handle_row_error(name, row, e.into(), policy, summary)?,
+            }
+        }
+        if batch.len() < IMPORT_BATCH_SIZE && !finished {
+            continue;
+        }
+        written += insert_batch(
+            name,
+            insert_func,
+            transaction,
+            std::mem::replace(&mut batch, Vec::with_capacity(IMPORT_BATCH_SIZE)),
+            &batch_rows,
+            policy,
+            summary,
+        )?;
+        batch_rows.clear();
+        (context.report_progress)(DumpProgressPayload {
+            name,
+            stage: "import",
+            bytes_processed: records.reader().get_ref().file_position(),
+            total_bytes: Some(total_bytes),
+            rows_processed: Some(row),
+        })
+        .map_err(|e| failure(Some(row), e))?;
+        if finished {
+            break;
+        }
+    }
+    summary.imported_rows.push((name.to_string(), row));
+    // Upserts skip unchanged rows, so whatever was written and didn't add a row updated one.
+    let written = written as u64;
+    if table == name {
+        let added = count_rows(transaction, table)
+            .map_err(|e| failure(Some(row), e))?
+            .saturating_sub(rows_before);
+        let changes = summary.changes_to(table);
+        changes.added += added;
+        changes.updated += written.saturating_sub(added);
+    } else {
+        summary.changes_to(table).removed += written;
+    }
+    Ok(())
+}
+
+fn count_rows(transaction: &Transaction, table: &str) -> Result<u64, Error> {
+    Ok(
+        transaction.query_row(&format!("SELECT COUNT(*) FROM {}", table), [], |row| {
+            row.get(0)
+        })?,
+    )
+}
+
+fn import_all_csvs(
+    context: &ImportContext,
+    from_directory: &Path,
+    transaction: &mut Transaction,
+    policy: RowErrorPolicy,
+    summary: &mut DumpImportSummary,
+) -> Result<(), DumpImportFailure> {
+    import_csv::<Cover>(
+        context,
+        from_directory,
+        "covers",
+        insert_covers,
+        transaction,
+        policy,
+        summary,
+    )?;
+    import_csv::<Website>(
+        context,
+        from_directory,
+        "websites",
+        insert_websites,
+        transaction,
+        policy,
+        summary,
+    )?;
+    import_csv::<Platform>(
+        context,
+        from_directory,
+        "platforms",
+        insert_platforms,
+        transaction,
+        policy,
+        summary,
+    )?;
+    let named_entities: [(&str, InsertFunc<NamedEntity>); 6] = [
+        ("genres", insert_genres),
+        ("themes", insert_themes),
+        ("game_modes", insert_game_modes),
+        ("franchises", insert_franchises),
+        ("collections", insert_collections),
+        ("companies", insert_companies),
+    ];
+    for (name, insert_func) in named_entities {
+        import_csv::<NamedEntity>(
+            context,
+            from_directory,
+            name,
+            insert_func,
+            transaction,
+            policy,
+            summary,
+        )?;
+    }
+    let skipped_before_games = summary.skipped_rows;
+    import_csv::<Game>(
+        context,
+        from_directory,
+        "games",
+        insert_games,
+        transaction,
+        policy,
+        summary,
+    )?;
+    // A full games dump lists every game, so anything it leaves out was removed upstream. Skipped
+    // rows would look removed too, so detection waits for a clean import.
+    if find_dump_file(from_directory, "games").is_some()
+        && find_dump_file(from_directory, "games_deleted").is_none()
+        && summary.skipped_rows == skipped_before_games
+    {
+        let removed = remove_missing_games(transaction)
+            .map_err(|e| DumpImportFailure::new(Some("games"), None, e))?;
+        summary.changes_to("games").removed += removed as u64;
+    }
+    let relinked = link_pending_similar_games(transaction)
+        .map_err(|e| DumpImportFailure::new(Some("games"), None, e))?;
+    summary.changes_to("games").updated += relinked as u64;
+    import_csv::<InvolvedCompany>(
+        context,
+        from_directory,
+        "involved_companies",
+        insert_involved_companies,
+        transaction,
+        policy,
+        summary,
+    )?;
+    import_csv::<GameReleaseDate>(
+        context,
+        from_directory,
+        "release_dates",
+        insert_release_dates,
+        transaction,
+        policy,
+        summary,
+    )?;
+    import_csv::<AlternativeName>(
+        context,
+        from_directory,
+        "alternative_names",
+        insert_alternative_names,
+        transaction,
+        policy,
+        summary,
+    )?;
+    import_csv::<GameLocalization>(
+        context,
+        from_directory,
+        "game_localizations",
+        insert_game_localizations,
+        transaction,
+        policy,
+        summary,
+    )?;
+    import_csv::<PopularityPrimitive>(
+        context,
+        from_directory,
+        "popularity_primitives",
+        insert_popularity_primitives,
+        transaction,
+        policy,
+        summary,
+    )?;
+    import_csv::<GameTimeToBeat>(
+        context,
+        from_directory,
+        "game_time_to_beats",
+        insert_game_time_to_beats,
+        transaction,
+        policy,
+        summary,
+    )?;
+    let deletions: [(&str, InsertFunc<DeletedRow>); 16] = [
+        ("game_time_to_beats_deleted", delete_game_time_to_beats),
+        (
+            "popularity_primitives_deleted",
+            delete_popularity_primitives,
+        ),
+        ("game_localizations_deleted", delete_game_localizations),
+        ("alternative_names_deleted", delete_alternative_names),
+        ("release_dates_deleted", delete_release_dates),
+        ("involved_companies_deleted", delete_involved_companies),
+        ("games_deleted", delete_games),
+        ("companies_deleted", delete_companies),
+        ("collections_deleted", delete_collections),
+        ("franchises_deleted", delete_franchises),
+        ("game_modes_deleted", delete_game_modes),
+        ("themes_deleted", delete_themes),
+        ("genres_deleted", delete_genres),
+        ("platforms_deleted", delete_platforms),
+        ("websites_deleted", delete_websites),
+        ("covers_deleted", delete_covers),
+    ];
+    for (name, delete_func) in deletions {
+        import_csv::<DeletedRow>(
+            context,
+            from_directory,
+            name,
+            delete_func,
+            transaction,
+            policy,
+            summary,
+        )?;
+    }
+    summary.removed_games =
+        get_removed_games(transaction).map_err(|e| DumpImportFailure::new(None, None, e))?;
+    Ok(())
+}
+
+fn create_import_tables(transaction: &Transaction) -> Result<(), Error> {
+    transaction.execute_batch(
+        "CREATE TEMP TABLE IF NOT EXISTS imported_game_ids (id INTEGER PRIMARY KEY);
+        CREATE TEMP TABLE IF NOT EXISTS removed_game_ids (id INTEGER PRIMARY KEY);
+        CREATE TEMP TABLE IF NOT EXISTS pending_similar_games (game_id INTEGER, similar_game_id INTEGER, PRIMARY KEY (game_id, similar_game_id));
+        CREATE TEMP TABLE IF NOT EXISTS changed_game_ids (id INTEGER PRIMARY KEY);
+        DELETE FROM temp.imported_game_ids;
+        DELETE FROM temp.removed_game_ids;
+        DELETE FROM temp.pending_similar_games;
+        DELETE FROM temp.changed_game_ids;",
+    )?;
+    Ok(())
+}
+
+fn remove_missing_games(transaction: &mut Transaction) -> Result<usize, Error> {
+    let rows = transaction
+        .prepare("SELECT id FROM games WHERE id NOT IN (SELECT id FROM temp.imported_game_ids)")?
+        .query_map([], |row| {
+            Ok(DeletedRow {
+                id: row.get(0)?,
+                merged_into: None,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    delete_games(transaction, &rows)
+}
+
+fn get_removed_games(transaction: &Transaction) -> Result<Vec<RemovedGame>, Error> {
+    let removed_games = transaction
+        .prepare("SELECT t.game_id, t.name, t.merged_into FROM game_tombstones t JOIN temp.removed_game_ids r ON r.id = t.game_id ORDER BY t.game_id")?
+        .query_map([], |row| {
+            Ok(RemovedGame {
+                game_id: row.get(0)?,
+                name: row.get(1)?,
+                merged_into: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(removed_games)
+}
+
+/// Follows merge redirects to a game that still exists. Chains are followed for a bounded number
+/// of hops so a redirect cycle can't loop forever.
+fn resolve_redirects(conn: &Connection) -> Result<(HashMap<i32, i32>, Vec<i32>), Error> {
+    let tombstones = conn
+        .prepare("SELECT game_id, merged_into FROM game_tombstones")?
+        .query_map([], |row| {
+            Ok((row.get::<_, i32>(0)?, row.get::<_, Option<i32>>(1)?))
+        })?
+        .collect::<Result<HashMap<_, _>, _>>()?;
+    let mut game_exists_stmt = conn.prepare("SELECT EXISTS (SELECT 1 FROM games WHERE id = ?1)")?;
+    let mut redirects = HashMap::new();
+    let mut unresolved = vec![];
+    for &game_id in tombstones.keys() {
+        let mut target = game_id;
+        let mut hops = 0;
+        while let Some(merged_into) = tombstones.get(&target) {
+            match merged_into {
+                Some(next) if hops < tombstones.len() => {
+                    target = *next;
+                    hops += 1;
+                }
+                _ => {
+                    unresolved.push(game_id);
+                    break;
+                }
+            }
+        }
+        if tombstones.contains_key(&target) {
+            continue;
+        }
+        if game_exists_stmt.query_row([target], |row| row.get::<_, bool>(0))? {
+            redirects.insert(game_id, target);
+        } else {
+            unresolved.push(game_id);
+        }
+    }
+    Ok((redirects, unresolved))
+}
+
+/// Points logs and executables at the game their game was merged into, and reports the removed
+/// games that logs still reference. Runs over every tombstone on each import, so it also catches
+/// rows added since the merge and retries after a failed run.
+fn remap_removed_games(
+    igdb_conn: &Connection,
+    logs_conn: &mut Connection,
+    summary: &mut DumpImportSummary,
 ) -> Result<(), Error> {
-    if file_path.exists() {
-        let items = parse_func(&file_path)?;
-        transaction.busy_timeout(std::time::Duration::from_secs(10))?;
-        insert_func(transaction, &items)?;
+    let (redirects, unresolved) = resolve_redirects(igdb_conn)?;
+    let transaction = logs_conn.transaction()?;
+    {
+        let mut update_logs_stmt =
+            transaction.prepare("UPDATE logs SET game_id = ?1 WHERE game_id = ?2")?;
+        let mut update_executables_stmt =
+            transaction.prepare("UPDATE executable_details SET game_id = ?1 WHERE game_id = ?2")?;
+        for (from_game_id, to_game_id) in &redirects {
+            let logs = update_logs_stmt.execute([to_game_id, from_game_id])?;
+            let executables = update_executables_stmt.execute([to_game_id, from_game_id])?;
+            if logs > 0 || executables > 0 {
+                summary.remapped_games.push(GameRemap {
+                    from_game_id: *from_game_id,
+                    to_game_id: *to_game_id,
+                    logs,
+                    executables,
+                });
+            }
+        }
+        let mut logged_stmt =
+            transaction.prepare("SELECT EXISTS (SELECT 1 FROM logs WHERE game_id = ?1)")?;
+        for game_id in unresolved {
+            if logged_stmt.query_row([game_id], |row| row.get::<_, bool>(0))? {
+                summary.unresolved_game_ids.push(game_id);
+            }
+        }
+    }
+    transaction.commit()?;
+    summary
+        .remapped_games
+        .sort_by_key(|remap| remap.from_game_id);
+    summary.unresolved_game_ids.sort();
+    Ok(())
+}
+
+pub fn run_import(
+    app_handle: &tauri::AppHandle,
+    from_directory: &Path,
+    policy: RowErrorPolicy,
+) -> Result<DumpImportSummary, DumpImportFailure> {
+    let setup_failure = |e: Error| DumpImportFailure::new(None, None, e);
+    let validation = validate_dumps(from_directory).map_err(setup_failure)?;
+    if validation.iter().any(|dump| dump.is_fatal(policy)) {
+        return Err(DumpImportFailure::invalid(validation));
+    }
+    let app_data_dir = get_app_data_directory(app_handle).map_err(setup_failure)?;
+    let staging_path = create_igdb_staging(&app_data_dir).map_err(setup_failure)?;
+    let context = ImportContext {
+        cancelled: &app_handle.state::<DumpImportState>().inner().cancelled,
+        report_progress: &|payload| Ok(app_handle.emit("dump_progress", payload)?),
+    };
+    let mut summary = match import_into(&context, &staging_path, from_directory, policy) {
+        Ok(summary) => summary,
+        Err(failure) => {
+            let _ = fs::remove_file(&staging_path);
+            return Err(failure);
+        }
+    };
+    let connections = app_handle.state::<DatabaseConnections>();
+    keep_previous_dump_versions(&app_data_dir).map_err(setup_failure)?;
+    swap_igdb_database(&app_data_dir, &connections.igdb_conn, &staging_path)
+        .map_err(setup_failure)?;
+    let igdb_conn = connections.igdb_conn.lock().unwrap();
+    let mut logs_conn = connections.logs_conn.lock().unwrap();
+    // The new catalogue is already live, and the next import retries the remap.
+    if let Err(e) = remap_removed_games(&igdb_conn, &mut logs_conn, &mut summary) {
+        summary.remapped_games.clear();
+        summary.unresolved_game_ids.clear();
+        summary.warnings.push(format!(
+            "Logs of removed titles could not be moved to the titles they were merged into: {}",
+            e
+        ));
+    }
+    Ok(summary)
+}
+
+fn import_into(
+    context: &ImportContext,
+    database_path: &Path,
+    from_directory: &Path,
+    policy: RowErrorPolicy,
+) -> Result<DumpImportSummary, DumpImportFailure> {
+    let setup_failure = |e: Error| DumpImportFailure::new(None, None, e);
+    let mut conn = Connection::open(database_path).map_err(|e| setup_failure(e.into()))?;
+    conn.execute_batch(
+        "PRAGMA synchronous = NORMAL; PRAGMA cache_size = -65536; PRAGMA temp_store = MEMORY;",
+    )
+    .map_err(|e| setup_failure(e.into()))?;
+    let mut transaction = conn.transaction().map_err(|e| setup_failure(e.into()))?;
+    create_import_tables(&transaction).map_err(setup_failure)?;
+    drop_search_triggers(&transaction).map_err(setup_failure)?;
+    let mut summary = DumpImportSummary::default();
+    if let Err(failure) = import_all_csvs(
+        context,
+        from_directory,
+        &mut transaction,
+        policy,
+        &mut summary,
+    ) {
+        transaction
+            .rollback()
+            .map_err(|e| setup_failure(e.into()))?;
+        return Err(failure);
+    }
+    rebuild_search_indexes(&transaction).map_err(setup_failure)?;
+    transaction.commit().map_err(|e| setup_failure(e.into()))?;
+    verify_igdb_database(&conn).map_err(setup_failure)?;
+    conn.close().map_err(|(_, e)| setup_failure(e.into()))?;
+    Ok(summary)
+}
+
+fn keep_previous_dump_versions(app_data_dir: &Path) -> Result<(), Error> {
+    let versions_path = app_data_dir.join("dump_versions.toml");
+    let previous_path = app_data_dir.join("dump_versions.previous.toml");
+    match versions_path.exists() {
+        true => fs::copy(versions_path, previous_path).map(|_| ())?,
+        false if previous_path.exists() => fs::remove_file(previous_path)?,
+        false => {}
     }
     Ok(())
 }
 
+/// Logs that the import moved to the game a removed title was merged into stay with that game.
 #[tauri::command]
-pub fn import_dumps(app_handle: tauri::AppHandle, from_directory: PathBuf) -> Result<(), Error> {
+pub fn rollback_dump_import(
+    app_handle: tauri::AppHandle,
+    connections: State<DatabaseConnections>,
+    import_state: State<DumpImportState>,
+) -> Result<(), Error> {
+    if !import_state.try_start() {
+        return Err(Error::from("A dump import is already running"));
+    }
+    let result = (|| {
+        let app_data_dir = get_app_data_directory(&app_handle)?;
+        rollback_igdb_database(&app_data_dir, &connections.igdb_conn)?;
+        let versions_path = app_data_dir.join("dump_versions.toml");
+        let previous_path = app_data_dir.join("dump_versions.previous.toml");
+        if previous_path.exists() {
+            fs::rename(previous_path, versions_path)?;
+        } else if versions_path.exists() {
+            fs::remove_file(versions_path)?;
+        }
+        Ok::<_, Error>(())
+    })();
+    import_state.finish();
+    result
+}
+
+#[tauri::command]
+pub fn can_rollback_dump_import(app_handle: tauri::AppHandle) -> Result<bool, Error> {
+    Ok(has_previous_igdb_database(&get_app_data_directory(
+        &app_handle,
+    )?))
+}
+
+#[tauri::command]
+pub fn import_dumps(
+    app_handle: tauri::AppHandle,
+    state: State<DumpImportState>,
+    from_directory: PathBuf,
+    row_error_policy: RowErrorPolicy,
+) -> Result<(), Error> {
+    if !state.try_start() {
+        return Err(Error::from("A dump import is already running"));
+    }
     thread::spawn(move || {
-        let app_data_dir = get_app_data_directory(&app_handle).unwrap();
-        let mut conn = Connection::open(app_data_dir.join("igdb.db")).unwrap();
-        let mut transaction = conn.transaction().unwrap();
-        import_csv(
-            from_directory.join("covers.csv"),
-            parse_csv::<Cover>,
-            insert_covers,
-            &mut transaction,
-        )
-        .unwrap();
-        import_csv(
-            from_directory.join("websites.csv"),
-            parse_csv::<Website>,
-            insert_websites,
-            &mut transaction,
-        )
-        .unwrap();
-        import_csv(
-            from_directory.join("platforms.csv"),
-            parse_csv::<Platform>,
-            insert_platforms,
-            &mut transaction,
-        )
-        .unwrap();
-        import_csv(
-            from_directory.join("games.csv"),
-            parse_csv::<Game>,
-            insert_games,
-            &mut transaction,
-        )
-        .unwrap();
-        import_csv(
-            from_directory.join("popularity_primitives.csv"),
-            parse_csv::<PopularityPrimitive>,
-            insert_popularity_primitives,
-            &mut transaction,
-        )
-        .unwrap();
-        transaction.commit().unwrap();
-        app_handle.emit("import_finished", "").unwrap();
+        let result = run_import(&app_handle, &from_directory, row_error_policy);
+        app_handle.state::<DumpImportState>().finish();
+        let _ = match result {
+            Ok(summary) => app_handle.emit("import_finished", summary),
+            Err(failure) => app_handle.emit("import_failed", failure),
+        };
     });
     Ok(())
 }
 
+#[tauri::command]
+pub fn cancel_import(state: State<DumpImportState>) -> Result<(), Error> {
+    state.cancelled.store(true, Ordering::Relaxed);
+    Ok(())
+}
+
 fn insert_covers(
     transaction: &mut rusqlite::Transaction,
     covers: &Vec<Cover>,
-) -> Result<(), Error> {
-    let mut select_stmt = transaction.prepare("SELECT image_id FROM covers WHERE id = ?1")?;
-    let mut insert_stmt =
-        transaction.prepare("INSERT INTO covers (id, image_id) VALUES (?1, ?2)")?;
-    let mut update_stmt = transaction.prepare("UPDATE covers SET image_id = ?1 WHERE id = ?2")?;
+) -> Result<usize, Error> {
+    let mut upsert_stmt = transaction.prepare("INSERT INTO covers (id, image_id) VALUES (?1, ?2) ON CONFLICT (id) DO UPDATE SET image_id = excluded.image_id WHERE image_id IS NOT excluded.image_id")?;
+    let mut written = 0;
     for cover in covers {
-        match select_stmt
-            .query_row(&[&cover.id], |row| row.get::<_, String>(0))
-            .optional()?
-        {
-            Some(i_id) => {
-                if i_id != cover.image_id {
-                    update_stmt.execute(params![&cover.image_id, &cover.id])?;
-                }
-            }
-            None => {
-                insert_stmt.execute((cover.id, &cover.image_id))?;
-            }
-        };
+        written += upsert_stmt.execute((cover.id, &cover.image_id))?;
     }
-    Ok(())
+    Ok(written)
 }
 
 fn insert_websites(
     transaction: &mut rusqlite::Transaction,
     websites: &Vec<Website>,
-) -> Result<(), Error> {
-    let mut select_stmt = transaction.prepare("SELECT url FROM websites WHERE id = ?1")?;
-    let mut insert_stmt = transaction.prepare("INSERT INTO websites (id, url) VALUES (?1, ?2)")?;
-    let mut update_stmt = transaction.prepare("UPDATE websites SET url = ?1 WHERE id = ?2")?;
+) -> Result<usize, Error> {
+    let mut upsert_stmt = transaction.prepare("INSERT INTO websites (id, url) VALUES (?1, ?2) ON CONFLICT (id) DO UPDATE SET url = excluded.url WHERE url IS NOT excluded.url")?;
+    let mut written = 0;
     for website in websites {
-        let url: Option<String> = select_stmt
-            .query_row(&[&website.id], |row| row.get(0))
-            .optional()?;
-        match url {
-            Some(u) => {
-                if u != website.url {
-                    update_stmt.execute(params![&website.url, &website.id])?;
-                }
-            }
-            None => {
-                insert_stmt.execute((website.id, &website.url))?;
-            }
-        }
+        written += upsert_stmt.execute((website.id, &website.url))?;
     }
-    Ok(())
+    Ok(written)
 }
 
 fn insert_platforms(
     transaction: &mut rusqlite::Transaction,
     platforms: &Vec<Platform>,
-) -> Result<(), Error> {
-    let mut select_stmt = transaction.prepare("SELECT * FROM platforms WHERE id = ?1")?;
-    let mut insert_stmt =
-        transaction.prepare("INSERT INTO platforms (id, name, category) VALUES (?1, ?2, ?3)")?;
-    let mut update_stmt =
-        transaction.prepare("UPDATE platforms SET name = ?1, category = ?2 WHERE id = ?3")?;
-    for csv_platform in platforms {
-        let platform: Option<Platform> = select_stmt
-            .query_row(&[&csv_platform.id], |row| {
-                Ok(Platform {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    category: row.get(2)?,
-                })
-            })
-            .optional()?;
-        match platform {
-            Some(p) => {
-                if p.name != csv_platform.name || p.category != csv_platform.category {
-                    update_stmt.execute((
-                        &csv_platform.name,
-                        csv_platform.category,
-                        csv_platform.id,
-                    ))?;
-                }
-            }
-            None => {
-                insert_stmt.execute((
-                    csv_platform.id,
-                    &csv_platform.name,
-                    csv_platform.category,
-                ))?;
+) -> Result<usize, Error> {
+    let mut upsert_stmt = transaction.prepare("INSERT INTO platforms (id, name, category) VALUES (?1, ?2, ?3) ON CONFLICT (id) DO UPDATE SET name = excluded.name, category = excluded.category WHERE name IS NOT excluded.name OR category IS NOT excluded.category")?;
+    let mut written = 0;
+    for platform in platforms {
+        written += upsert_stmt.execute((platform.id, &platform.name, platform.category))?;
+    }
+    Ok(written)
+}
+
+fn insert_games(
+    transaction: &mut rusqlite::Transaction,
+    games: &Vec<Game>,
+) -> Result<usize, Error> {
+    let mut changed_game_ids = HashSet::new();
+    {
+        let mut upsert_game_stmt = transaction.prepare("INSERT INTO games (id, name, cover_id, category, version_parent, total_rating, summary, storyline) VALUES (?1, ?2, (SELECT id FROM covers WHERE id = ?3), ?4, ?5, ?6, ?7, ?8) ON CONFLICT (id) DO UPDATE SET name = excluded.name, cover_id = excluded.cover_id, category = excluded.category, version_parent = excluded.version_parent, total_rating = excluded.total_rating, summary = excluded.summary, storyline = excluded.storyline WHERE name IS NOT excluded.name OR cover_id IS NOT excluded.cover_id OR category IS NOT excluded.category OR version_parent IS NOT excluded.version_parent OR total_rating IS NOT excluded.total_rating OR summary IS NOT excluded.summary OR storyline IS NOT excluded.storyline")?;
+        let mut imported_game_stmt =
+            transaction.prepare("INSERT OR IGNORE INTO temp.imported_game_ids (id) VALUES (?1)")?;
+        let mut restore_game_stmt =
+            transaction.prepare("DELETE FROM game_tombstones WHERE game_id = ?1")?;
+        for csv_game in games {
+            let written = upsert_game_stmt.execute((
+                csv_game.id,
+                &csv_game.name,
+                csv_game.cover_id,
+                csv_game.category,
+                csv_game.version_parent,
+                csv_game.total_rating,
+                &csv_game.summary,
+                &csv_game.storyline,
+            ))?;
+            if written > 0 {
+                changed_game_ids.insert(csv_game.id);
             }
+            imported_game_stmt.execute([csv_game.id])?;
+            restore_game_stmt.execute([csv_game.id])?;
         }
     }
-    Ok(())
+    for (index, link_table) in GAME_LINK_TABLES.into_iter().enumerate() {
+        let links = games.iter().filter_map(|game| {
+            game_link_ids(game)[index]
+                .as_ref()
+                .map(|ids| (game.id, ids))
+        });
+        changed_game_ids.extend(sync_game_links(transaction, link_table, links)?);
+    }
+    mark_games_changed(transaction, changed_game_ids)
 }
 
-fn insert_games(transaction: &mut rusqlite::Transaction, games: &Vec<Game>) -> Result<(), Error> {
-    let mut select_game_stmt =
-        transaction.prepare("SELECT g.id, g.name, g.cover_id, g.category, g.version_parent, g.total_rating, GROUP_CONCAT(w.id, ','), GROUP_CONCAT(p.id, ','), GROUP_CONCAT(sg.game_id, ',') FROM games g LEFT JOIN covers c ON c.id = g.cover_id LEFT JOIN game_websites gw ON gw.game_id = g.id LEFT JOIN websites w ON w.id = gw.website_id LEFT JOIN similar_games sg ON sg.game_id = g.id LEFT JOIN game_platforms gp ON gp.game_id = g.id LEFT JOIN platforms p ON p.id = gp.platform_id WHERE g.id = ?1 GROUP BY g.id;")?;
-    let mut insert_game_stmt = transaction.prepare("INSERT INTO games (id, name, cover_id, category, version_parent, total_rating) VALUES (?1, ?2, ?3, ?4, ?5, ?6)")?;
-    let mut update_game_stmt = transaction.prepare("UPDATE games SET name = ?1, cover_id = ?2, category = ?3, version_parent = ?4, total_rating = ?5 WHERE id = ?6")?;
-    let mut insert_game_websites_stmt =
-        transaction.prepare("INSERT INTO game_websites (game_id, website_id) VALUES (?1, ?2)")?;
-    let mut delete_game_websites_stmt =
-        transaction.prepare("DELETE FROM game_websites WHERE game_id = ?1")?;
-    let mut insert_similar_games_stmt = transaction
-        .prepare("INSERT INTO similar_games (game_id, similar_game_id) VALUES (?1, ?2)")?;
-    let mut delete_similar_games_stmt =
-        transaction.prepare("DELETE FROM similar_games WHERE game_id = ?1")?;
-    let mut insert_game_platforms_stmt =
-        transaction.prepare("INSERT INTO game_platforms (game_id, platform_id) VALUES (?1, ?2)")?;
-    let mut delete_game_platforms_stmt =
-        transaction.prepare("DELETE FROM game_platforms WHERE game_id = ?1")?;
-    let mut select_cover_stmt = transaction.prepare("SELECT id FROM covers WHERE id = ?1")?;
-
-    for csv_game in games {
-        let result = select_game_stmt
-            .query_row(&[&csv_game.id], |row| {
-                let website_ids: Option<Vec<i32>> = match row.get::<usize, Option<String>>(6)? {
-                    Some(string) => Some(
-                        string
-                            .split(',')
-                            .map(|s| s.parse::<i32>().unwrap())
-                            .collect(),
-                    ),
-                    None => None,
-                };
-                let platform_ids: Option<Vec<i32>> = match row.get::<usize, Option<String>>(7)? {
-                    Some(string) => Some(
-                        string
-                            .split(',')
-                            .map(|s| s.parse::<i32>().unwrap())
-                            .collect(),
-                    ),
-                    None => None,
-                };
-                let similar_games: Option<Vec<i32>> = match row.get::<usize, Option<String>>(8)? {
-                    Some(string) => Some(
-                        string
-                            .split(',')
-                            .map(|s| s.parse::<i32>().unwrap())
-                            .collect(),
-                    ),
-                    None => None,
-                };
-                let cover_id = row.get::<usize, Option<i32>>(2)?;
-                let g = Game {
-                    id: row.get(0)?,
-                    name: row.get(1)?,
-                    cover_id,
-                    category: row.get(3)?,
-                    version_parent: row.get(4)?,
-                    total_rating: row.get(5)?,
-                    website_ids: website_ids.clone(),
-                    similar_games: similar_games.clone(),
-                    platform_ids: platform_ids.clone(),
-                };
-                Ok(g)
-            })
-            .optional()?;
-        match result {
-            Some(db_game) => {
-                if db_game.name != csv_game.name
-                    || db_game.cover_id != csv_game.cover_id
-                    || db_game.category != csv_game.category
-                    || db_game.version_parent != csv_game.version_parent
-                    || db_game.total_rating != csv_game.total_rating
-                {
-                    let cover_id = match csv_game.cover_id {
-                        Some(id) => match select_cover_stmt
-                            .query_row(&[&id], |row| row.get::<usize, i32>(0))
-                            .optional()?
-                        {
-                            Some(_) => Some(id),
-                            None => None,
-                        },
-                        None => None,
-                    };
-                    update_game_stmt.execute((
-                        &csv_game.name,
-                        cover_id,
-                        csv_game.category,
-                        csv_game.version_parent,
-                        csv_game.total_rating,
-                        csv_game.id,
-                    ))?;
-                }
-                if let Some(website_ids) = &csv_game.website_ids {
-                    delete_game_websites_stmt.execute(&[&csv_game.id])?;
-                    for website_id in website_ids {
-                        insert_game_websites_stmt.execute((csv_game.id, website_id))?;
-                    }
-                }
-                if let Some(similar_games) = &csv_game.similar_games {
-                    delete_similar_games_stmt.execute(&[&csv_game.id])?;
-                    for similar_game_id in similar_games {
-                        insert_similar_games_stmt.execute((csv_game.id, similar_game_id))?;
-                    }
-                }
-                if let Some(platform_ids) = &csv_game.platform_ids {
-                    delete_game_platforms_stmt.execute(&[&csv_game.id])?;
-                    for platform_id in platform_ids {
-                        insert_game_platforms_stmt.execute((csv_game.id, platform_id))?;
-                    }
-                }
+/// Counts each game once however many of its rows and links changed, including links added after
+/// its batch. Returns how many games weren't already counted.
+fn mark_games_changed(
+    transaction: &Transaction,
+    game_ids: impl IntoIterator<Item = i32>,
+) -> Result<usize, Error> {
+    let mut stmt = transaction
+        .prepare_cached("INSERT OR IGNORE INTO temp.changed_game_ids (id) VALUES (?1)")?;
+    let mut newly_changed = 0;
+    for game_id in game_ids {
+        newly_changed += stmt.execute([game_id])?;
+    }
+    Ok(newly_changed)
+}
+
+fn link_pending_similar_games(transaction: &Transaction) -> Result<usize, Error> {
+    let linked_game_ids = transaction
+        .prepare("INSERT OR IGNORE INTO similar_games (game_id, similar_game_id) SELECT p.game_id, g.id FROM temp.pending_similar_games p JOIN games g ON g.id = p.similar_game_id RETURNING game_id")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<HashSet<i32>, _>>()?;
+    mark_games_changed(transaction, linked_game_ids)
+}
+
+/// The id lists of a game, in `GAME_LINK_TABLES` order.
+fn game_link_ids(game: &Game) -> [&Option<Vec<i32>>; 8] {
+    [
+        &game.website_ids,
+        &game.platform_ids,
+        &game.similar_games,
+        &game.genres,
+        &game.themes,
+        &game.game_modes,
+        &game.franchises,
+        &game.collections,
+    ]
+}
+
+fn sync_game_links<'a>(
+    transaction: &mut rusqlite::Transaction,
+    (link_table, link_column, table): (&str, &str, &str),
+    links: impl Iterator<Item = (i32, &'a Vec<i32>)>,
+) -> Result<HashSet<i32>, Error> {
+    let links = links.collect::<Vec<_>>();
+    let mut changed_game_ids = HashSet::new();
+    if links.is_empty() {
+        return Ok(changed_game_ids);
+    }
+    let game_ids = serde_json::to_string(&links.iter().map(|(id, _)| id).collect::<Vec<_>>())?;
+    let mut existing = transaction
+        .prepare_cached(&format!(
+            "SELECT game_id, {link_column} FROM {link_table} WHERE game_id IN (SELECT value FROM json_each(?1))"
+        ))?
+        .query_map([game_ids], |row| Ok((row.get::<_, i32>(0)?, row.get::<_, i32>(1)?)))?
+        .collect::<Result<HashSet<_>, _>>()?;
+    let mut added = vec![];
+    for (game_id, ids) in links {
+        for id in ids {
+            if !existing.remove(&(game_id, *id)) {
+                added.push((game_id, *id));
             }
-            None => {
-                let cover_id = match csv_game.cover_id {
-                    Some(id) => match select_cover_stmt
-                        .query_row(&[&id], |row| row.get::<usize, i32>(0))
-                        .optional()?
-                    {
-                        Some(_) => Some(id),
-                        None => None,
-                    },
-                    None => None,
-                };
-                insert_game_stmt.execute((
-                    csv_game.id,
-                    &csv_game.name,
-                    cover_id,
-                    csv_game.category,
-                    csv_game.version_parent,
-                    csv_game.total_rating,
+        }
+    }
+    if !added.is_empty() {
+        let added = serde_json::to_string(&added)?;
+        match table {
+            // Similar games can point at a game a later batch adds, so they wait in a scratch
+            // table until the whole games dump is in.
+            "games" => {
+                transaction
+                    .prepare_cached("INSERT OR IGNORE INTO temp.pending_similar_games (game_id, similar_game_id) SELECT json_extract(value, '$[0]'), json_extract(value, '$[1]') FROM json_each(?1)")?
+                    .execute([added])?;
+            }
+            _ => {
+                let mut insert_stmt = transaction.prepare_cached(&format!(
+                    "INSERT OR IGNORE INTO {link_table} (game_id, {link_column}) SELECT json_extract(a.value, '$[0]'), t.id FROM json_each(?1) a JOIN {table} t ON t.id = json_extract(a.value, '$[1]') RETURNING game_id"
                 ))?;
-                if let Some(website_ids) = &csv_game.website_ids {
-                    for website_id in website_ids {
-                        insert_game_websites_stmt.execute((csv_game.id, website_id))?;
-                    }
-                }
-                if let Some(platform_ids) = &csv_game.platform_ids {
-                    for platform_id in platform_ids {
-                        insert_game_platforms_stmt.execute((csv_game.id, platform_id))?;
-                    }
+                for game_id in insert_stmt.query_map([added], |row| row.get(0))? {
+                    changed_game_ids.insert(game_id?);
                 }
             }
         }
     }
-    Ok(())
+    let mut delete_stmt = transaction.prepare_cached(&format!(
+        "DELETE FROM {link_table} WHERE game_id = ?1 AND {link_column} = ?2"
+    ))?;
+    for (game_id, id) in existing {
+        delete_stmt.execute([game_id, id])?;
+        changed_game_ids.insert(game_id);
+    }
+    Ok(changed_game_ids)
+}
+
+fn insert_named_entities(
+    transaction: &mut rusqlite::Transaction,
+    table: &str,
+    entities: &Vec<NamedEntity>,
+) -> Result<usize, Error> {
+    let mut upsert_stmt = transaction.prepare(&format!(
+        "INSERT INTO {} (id, name) VALUES (?1, ?2) ON CONFLICT (id) DO UPDATE SET name = excluded.name WHERE name IS NOT excluded.name",
+        table
+    ))?;
+    let mut written = 0;
+    for entity in entities {
+        written += upsert_stmt.execute((entity.id, &entity.name))?;
+    }
+    Ok(written)
+}
+
+fn insert_genres(
+    transaction: &mut rusqlite::Transaction,
+    genres: &Vec<NamedEntity>,
+) -> Result<usize, Error> {
+    insert_named_entities(transaction, "genres", genres)
+}
+
+fn insert_themes(
+    transaction: &mut rusqlite::Transaction,
+    themes: &Vec<NamedEntity>,
+) -> Result<usize, Error> {
+    insert_named_entities(transaction, "themes", themes)
+}
+
+fn insert_game_modes(
+    transaction: &mut rusqlite::Transaction,
+    game_modes: &Vec<NamedEntity>,
+) -> Result<usize, Error> {
+    insert_named_entities(transaction, "game_modes", game_modes)
+}
+
+fn insert_franchises(
+    transaction: &mut rusqlite::Transaction,
+    franchises: &Vec<NamedEntity>,
+) -> Result<usize, Error> {
+    insert_named_entities(transaction, "franchises", franchises)
+}
+
+fn insert_collections(
+    transaction: &mut rusqlite::Transaction,
+    collections: &Vec<NamedEntity>,
+) -> Result<usize, Error> {
+    insert_named_entities(transaction, "collections", collections)
+}
+
+fn insert_companies(
+    transaction: &mut rusqlite::Transaction,
+    companies: &Vec<NamedEntity>,
+) -> Result<usize, Error> {
+    insert_named_entities(transaction, "companies", companies)
+}
+
+fn insert_involved_companies(
+    transaction: &mut rusqlite::Transaction,
+    involved_companies: &Vec<InvolvedCompany>,
+) -> Result<usize, Error> {
+    let mut upsert_stmt = transaction.prepare("INSERT INTO involved_companies (id, game_id, company_id, developer, publisher) SELECT ?1, g.id, c.id, ?4, ?5 FROM games g, companies c WHERE g.id = ?2 AND c.id = ?3 ON CONFLICT (id) DO UPDATE SET game_id = excluded.game_id, company_id = excluded.company_id, developer = excluded.developer, publisher = excluded.publisher WHERE game_id IS NOT excluded.game_id OR company_id IS NOT excluded.company_id OR developer IS NOT excluded.developer OR publisher IS NOT excluded.publisher")?;
+    let mut written = 0;
+    for involved_company in involved_companies {
+        written += upsert_stmt.execute((
+            involved_company.id,
+            involved_company.game_id,
+            involved_company.company_id,
+            involved_company.developer,
+            involved_company.publisher,
+        ))?;
+    }
+    Ok(written)
+}
+
+fn insert_release_dates(
+    transaction: &mut rusqlite::Transaction,
+    release_dates: &Vec<GameReleaseDate>,
+) -> Result<usize, Error> {
+    let mut upsert_stmt = transaction.prepare("INSERT INTO release_dates (id, game_id, platform_id, date, region) SELECT ?1, id, ?3, ?4, ?5 FROM games WHERE id = ?2 ON CONFLICT (id) DO UPDATE SET game_id = excluded.game_id, platform_id = excluded.platform_id, date = excluded.date, region = excluded.region WHERE game_id IS NOT excluded.game_id OR platform_id IS NOT excluded.platform_id OR date IS NOT excluded.date OR region IS NOT excluded.region")?;
+    let mut written = 0;
+    for release_date in release_dates {
+        written += upsert_stmt.execute((
+            release_date.id,
+            release_date.game_id,
+            release_date.platform_id,
+            release_date.date,
+            release_date.region,
+        ))?;
+    }
+    Ok(written)
 }
 
 pub fn insert_popularity_primitives(
     transaction: &mut rusqlite::Transaction,
     popularity_primitives: &Vec<PopularityPrimitive>,
-) -> Result<(), Error> {
-    let mut select_stmt =
-        transaction.prepare("SELECT * FROM popularity_primitives WHERE id = ?1")?;
-    let mut insert_stmt = transaction.prepare("INSERT INTO popularity_primitives (id, game_id, popularity_type, value) VALUES (?1, ?2, ?3, ?4)")?;
-    let mut update_stmt = transaction.prepare("UPDATE popularity_primitives SET game_id = ?1, popularity_type = ?2, value = ?3 WHERE id = ?4")?;
-    for csv_popularity_primitive in popularity_primitives {
-        let popularity_primitive: Option<PopularityPrimitive> = select_stmt
-            .query_row(&[&csv_popularity_primitive.id], |row| {
-                Ok(PopularityPrimitive {
-                    id: row.get(0)?,
-                    game_id: row.get(1)?,
-                    popularity_type: row.get(2)?,
-                    value: row.get(3)?,
-                })
-            })
-            .optional()?;
-        match popularity_primitive {
-            Some(p) => {
-                if p.game_id != csv_popularity_primitive.game_id
-                    || p.popularity_type != csv_popularity_primitive.popularity_type
-                    || p.value != csv_popularity_primitive.value
-                {
-                    update_stmt.execute((
-                        csv_popularity_primitive.game_id,
-                        csv_popularity_primitive.popularity_type,
-                        csv_popularity_primitive.value,
-                        csv_popularity_primitive.id,
-                    ))?;
-                }
-            }
-            None => {
-                match transaction
-                    .query_row(
-                        "SELECT id FROM games WHERE id = ?",
-                        params![csv_popularity_primitive.game_id],
-                        |row| row.get::<usize, i32>(0),
-                    )
-                    .optional()?
-                {
-                    Some(_) => {}
-                    None => {
-                        continue;
-                    }
-                };
-                insert_stmt.execute((
-                    csv_popularity_primitive.id,
-                    csv_popularity_primitive.game_id,
-                    csv_popularity_primitive.popularity_type,
-                    csv_popularity_primitive.value,
-                ))?;
-            }
+) -> Result<usize, Error> {
+    let mut upsert_stmt = transaction.prepare("INSERT INTO popularity_primitives (id, game_id, popularity_type, value) SELECT ?1, id, ?3, ?4 FROM games WHERE id = ?2 ON CONFLICT (id) DO UPDATE SET game_id = excluded.game_id, popularity_type = excluded.popularity_type, value = excluded.value WHERE game_id IS NOT excluded.game_id OR popularity_type IS NOT excluded.popularity_type OR value IS NOT excluded.value")?;
+    let mut written = 0;
+    for popularity_primitive in popularity_primitives {
+        written += upsert_stmt.execute((
+            popularity_primitive.id,
+            popularity_primitive.game_id,
+            popularity_primitive.popularity_type,
+            popularity_primitive.value,
+        ))?;
+    }
+    Ok(written)
+}
+
+pub fn insert_game_time_to_beats(
+    transaction: &mut rusqlite::Transaction,
+    game_time_to_beats: &Vec<GameTimeToBeat>,
+) -> Result<usize, Error> {
+    let mut upsert_stmt = transaction.prepare("INSERT INTO game_time_to_beats (id, game_id, hastily, normally, completely) SELECT ?1, id, ?3, ?4, ?5 FROM games WHERE id = ?2 ON CONFLICT (id) DO UPDATE SET game_id = excluded.game_id, hastily = excluded.hastily, normally = excluded.normally, completely = excluded.completely WHERE game_id IS NOT excluded.game_id OR hastily IS NOT excluded.hastily OR normally IS NOT excluded.normally OR completely IS NOT excluded.completely")?;
+    let mut written = 0;
+    for time_to_beat in game_time_to_beats {
+        written += upsert_stmt.execute((
+            time_to_beat.id,
+            time_to_beat.game_id,
+            time_to_beat.hastily,
+            time_to_beat.normally,
+            time_to_beat.completely,
+        ))?;
+    }
+    Ok(written)
+}
+
+fn insert_alternative_names(
+    transaction: &mut rusqlite::Transaction,
+    alternative_names: &Vec<AlternativeName>,
+) -> Result<usize, Error> {
+    let mut upsert_stmt = transaction.prepare("INSERT INTO alternative_names (id, game_id, name, comment) SELECT ?1, id, ?3, ?4 FROM games WHERE id = ?2 ON CONFLICT (id) DO UPDATE SET game_id = excluded.game_id, name = excluded.name, comment = excluded.comment WHERE game_id IS NOT excluded.game_id OR name IS NOT excluded.name OR comment IS NOT excluded.comment")?;
+    let mut written = 0;
+    for alternative_name in alternative_names {
+        written += upsert_stmt.execute((
+            alternative_name.id,
+            alternative_name.game_id,
+            &alternative_name.name,
+            &alternative_name.comment,
+        ))?;
+    }
+    Ok(written)
+}
+
+fn insert_game_localizations(
+    transaction: &mut rusqlite::Transaction,
+    game_localizations: &Vec<GameLocalization>,
+) -> Result<usize, Error> {
+    let mut upsert_stmt = transaction.prepare("INSERT INTO game_localizations (id, game_id, name, region) SELECT ?1, id, ?3, ?4 FROM games WHERE id = ?2 ON CONFLICT (id) DO UPDATE SET game_id = excluded.game_id, name = excluded.name, region = excluded.region WHERE game_id IS NOT excluded.game_id OR name IS NOT excluded.name OR region IS NOT excluded.region")?;
+    let mut written = 0;
+    for game_localization in game_localizations {
+        written += upsert_stmt.execute((
+            game_localization.id,
+            game_localization.game_id,
+            &game_localization.name,
+            game_localization.region,
+        ))?;
+    }
+    Ok(written)
+}
+
+/// Returns how many rows the last statement removed, which is the one deleting from the dump's own
+/// table.
+fn delete_rows(
+    transaction: &mut rusqlite::Transaction,
+    statements: &[&str],
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    let mut deleted = 0;
+    for statement in statements {
+        let mut stmt = transaction.prepare(statement)?;
+        deleted = 0;
+        for row in rows {
+            deleted += stmt.execute([row.id])?;
         }
     }
-    Ok(())
+    Ok(deleted)
+}
+
+fn delete_covers(
+    transaction: &mut rusqlite::Transaction,
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    delete_rows(
+        transaction,
+        &[
+            "UPDATE games SET cover_id = NULL WHERE cover_id = ?1",
+            "DELETE FROM covers WHERE id = ?1",
+        ],
+        rows,
+    )
+}
+
+fn delete_websites(
+    transaction: &mut rusqlite::Transaction,
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    delete_rows(
+        transaction,
+        &[
+            "DELETE FROM game_websites WHERE website_id = ?1",
+            "DELETE FROM websites WHERE id = ?1",
+        ],
+        rows,
+    )
+}
+
+fn delete_platforms(
+    transaction: &mut rusqlite::Transaction,
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    delete_rows(
+        transaction,
+        &[
+            "DELETE FROM game_platforms WHERE platform_id = ?1",
+            "DELETE FROM platforms WHERE id = ?1",
+        ],
+        rows,
+    )
+}
+
+/// Removed games leave a tombstone behind, with a redirect when they were merged into another
+/// game, so logs pointing at them can be remapped.
+fn delete_games(
+    transaction: &mut rusqlite::Transaction,
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    {
+        let mut tombstone_stmt = transaction.prepare("INSERT OR REPLACE INTO game_tombstones (game_id, name, merged_into) VALUES (?1, COALESCE((SELECT name FROM games WHERE id = ?1), (SELECT name FROM game_tombstones WHERE game_id = ?1)), ?2)")?;
+        let mut removed_stmt =
+            transaction.prepare("INSERT OR IGNORE INTO temp.removed_game_ids (id) VALUES (?1)")?;
+        for row in rows {
+            tombstone_stmt.execute(params![row.id, row.merged_into])?;
+            removed_stmt.execute([row.id])?;
+        }
+    }
+    delete_rows(
+        transaction,
+        &[
+            "DELETE FROM game_websites WHERE game_id = ?1",
+            "DELETE FROM game_platforms WHERE game_id = ?1",
+            "DELETE FROM similar_games WHERE game_id = ?1 OR similar_game_id = ?1",
+            "DELETE FROM popularity_primitives WHERE game_id = ?1",
+            "DELETE FROM game_time_to_beats WHERE game_id = ?1",
+            "DELETE FROM game_genres WHERE game_id = ?1",
+            "DELETE FROM game_themes WHERE game_id = ?1",
+            "DELETE FROM game_game_modes WHERE game_id = ?1",
+            "DELETE FROM game_franchises WHERE game_id = ?1",
+            "DELETE FROM game_collections WHERE game_id = ?1",
+            "DELETE FROM involved_companies WHERE game_id = ?1",
+            "DELETE FROM release_dates WHERE game_id = ?1",
+            "DELETE FROM alternative_names WHERE game_id = ?1",
+            "DELETE FROM game_localizations WHERE game_id = ?1",
+            "DELETE FROM games WHERE id = ?1",
+        ],
+        rows,
+    )
+}
+
+fn delete_popularity_primitives(
+    transaction: &mut rusqlite::Transaction,
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    delete_rows(
+        transaction,
+        &["DELETE FROM popularity_primitives WHERE id = ?1"],
+        rows,
+    )
+}
+
+fn delete_game_time_to_beats(
+    transaction: &mut rusqlite::Transaction,
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    delete_rows(
+        transaction,
+        &["DELETE FROM game_time_to_beats WHERE id = ?1"],
+        rows,
+    )
+}
+
+fn delete_genres(
+    transaction: &mut rusqlite::Transaction,
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    delete_rows(
+        transaction,
+        &[
+            "DELETE FROM game_genres WHERE genre_id = ?1",
+            "DELETE FROM genres WHERE id = ?1",
+        ],
+        rows,
+    )
+}
+
+fn delete_themes(
+    transaction: &mut rusqlite::Transaction,
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    delete_rows(
+        transaction,
+        &[
+            "DELETE FROM game_themes WHERE theme_id = ?1",
+            "DELETE FROM themes WHERE id = ?1",
+        ],
+        rows,
+    )
+}
+
+fn delete_game_modes(
+    transaction: &mut rusqlite::Transaction,
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    delete_rows(
+        transaction,
+        &[
+            "DELETE FROM game_game_modes WHERE game_mode_id = ?1",
+            "DELETE FROM game_modes WHERE id = ?1",
+        ],
+        rows,
+    )
+}
+
+fn delete_franchises(
+    transaction: &mut rusqlite::Transaction,
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    delete_rows(
+        transaction,
+        &[
+            "DELETE FROM game_franchises WHERE franchise_id = ?1",
+            "DELETE FROM franchises WHERE id = ?1",
+        ],
+        rows,
+    )
+}
+
+fn delete_collections(
+    transaction: &mut rusqlite::Transaction,
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    delete_rows(
+        transaction,
+        &[
+            "DELETE FROM game_collections WHERE collection_id = ?1",
+            "DELETE FROM collections WHERE id = ?1",
+        ],
+        rows,
+    )
+}
+
+fn delete_companies(
+    transaction: &mut rusqlite::Transaction,
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    delete_rows(
+        transaction,
+        &[
+            "DELETE FROM involved_companies WHERE company_id = ?1",
+            "DELETE FROM companies WHERE id = ?1",
+        ],
+        rows,
+    )
+}
+
+fn delete_involved_companies(
+    transaction: &mut rusqlite::Transaction,
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    delete_rows(
+        transaction,
+        &["DELETE FROM involved_companies WHERE id = ?1"],
+        rows,
+    )
+}
+
+fn delete_release_dates(
+    transaction: &mut rusqlite::Transaction,
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    delete_rows(
+        transaction,
+        &["DELETE FROM release_dates WHERE id = ?1"],
+        rows,
+    )
+}
+
+fn delete_alternative_names(
+    transaction: &mut rusqlite::Transaction,
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    delete_rows(
+        transaction,
+        &["DELETE FROM alternative_names WHERE id = ?1"],
+        rows,
+    )
+}
+
+fn delete_game_localizations(
+    transaction: &mut rusqlite::Transaction,
+    rows: &Vec<DeletedRow>,
+) -> Result<usize, Error> {
+    delete_rows(
+        transaction,
+        &["DELETE FROM game_localizations WHERE id = ?1"],
+        rows,
+    )
 }
 
 #[tauri::command]
@@ -489,3 +1906,503 @@ pub fn save_local_dump_versions(
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::Instant;
+
+    use super::*;
+
+    fn benchmark_size(variable: &str, default: i32) -> i32 {
+        std::env::var(variable)
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(default)
+    }
+
+    fn fixture_directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!("game-chronicle-{}", name));
+        let _ = fs::remove_dir_all(&directory);
+        fs::create_dir_all(&directory).unwrap();
+        directory
+    }
+
+    fn write_csv(directory: &Path, table: &str, header: &str, rows: impl Iterator<Item = String>) {
+        let mut file =
+            BufWriter::new(File::create(directory.join(format!("{}.csv", table))).unwrap());
+        writeln!(file, "{}", header).unwrap();
+        for row in rows {
+            writeln!(file, "{}", row).unwrap();
+        }
+        file.flush().unwrap();
+    }
+
+    fn write_games_csv(directory: &Path, games: i32, renamed: impl Fn(i32) -> bool) {
+        write_csv(
+            directory,
+            "games",
+            "id,name,cover,websites,similar_games,category,version_parent,total_rating,platforms",
+            (1..=games).map(|id| {
+                format!(
+                    "{},Game {}{},,{{}},\"{{{},{}}}\",0,,{},",
+                    id,
+                    id,
+                    if renamed(id) { " (renamed)" } else { "" },
+                    id % games + 1,
+                    (id + 7) % games + 1,
+                    id % 100
+                )
+            }),
+        );
+    }
+
+    fn create_database(path: &Path) {
+        Connection::open(path)
+            .unwrap()
+            .execute_batch(
+                &[
+                    include_str!("../sql/initialize_igdb_database.sql"),
+                    include_str!("../sql/initialize_search_triggers.sql"),
+                ]
+                .concat(),
+            )
+            .unwrap();
+    }
+
+    fn import(database_path: &Path, from_directory: &Path) -> DumpImportSummary {
+        let cancelled = AtomicBool::new(false);
+        let context = ImportContext {
+            cancelled: &cancelled,
+            report_progress: &|_| Ok(()),
+        };
+        import_into(
+            &context,
+            database_path,
+            from_directory,
+            RowErrorPolicy::Abort,
+        )
+        .map_err(|failure| failure.message)
+        .unwrap()
+    }
+
+    /// Imports the way it was done before the search triggers were dropped during imports, as a
+    /// baseline for the benchmark.
+    fn import_with_search_triggers(database_path: &Path, from_directory: &Path) {
+        let cancelled = AtomicBool::new(false);
+        let context = ImportContext {
+            cancelled: &cancelled,
+            report_progress: &|_| Ok(()),
+        };
+        let mut conn = Connection::open(database_path).unwrap();
+        conn.execute_batch(
+            "PRAGMA synchronous = NORMAL; PRAGMA cache_size = -65536; PRAGMA temp_store = MEMORY;",
+        )
+        .unwrap();
+        let mut transaction = conn.transaction().unwrap();
+        create_import_tables(&transaction).unwrap();
+        import_all_csvs(
+            &context,
+            from_directory,
+            &mut transaction,
+            RowErrorPolicy::Abort,
+            &mut DumpImportSummary::default(),
+        )
+        .map_err(|failure| failure.message)
+        .unwrap();
+        transaction.commit().unwrap();
+    }
+
+    /// The process's peak resident memory, which Linux reports in /proc.
+    fn peak_memory_kib() -> Option<u64> {
+        fs::read_to_string("/proc/self/status")
+            .ok()?
+            .lines()
+            .find_map(|line| line.strip_prefix("VmHWM:"))?
+            .trim()
+            .trim_end_matches("kB")
+            .trim()
+            .parse()
+            .ok()
+    }
+
+    // cargo test --release streaming_import -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn streaming_import_benchmark() {
+        let rows = benchmark_size("BENCH_ROWS", 3_000_000);
+        let games = (rows / 10).max(1);
+        let directory = fixture_directory("streaming-import");
+        write_games_csv(&directory, games, |_| false);
+        write_csv(
+            &directory,
+            "popularity_primitives",
+            "id,game_id,popularity_type,value",
+            (1..=rows).map(|id| format!("{},{},{},{}", id, id % games + 1, id % 8, id % 1000)),
+        );
+        let database_path = directory.join("igdb.db");
+        create_database(&database_path);
+        let memory_before = peak_memory_kib();
+        let start = Instant::now();
+        let summary = import(&database_path, &directory);
+        let elapsed = start.elapsed();
+        println!(
+            "imported {} popularity rows and {} games in {:.2?}, peak memory {:?} KiB before and {:?} KiB after",
+            rows,
+            games,
+            elapsed,
+            memory_before,
+            peak_memory_kib()
+        );
+        assert!(summary
+            .imported_rows
+            .contains(&("popularity_primitives".to_string(), rows as u64)));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    // cargo test --release bulk_upsert -- --ignored --nocapture
+    #[test]
+    #[ignore]
+    fn bulk_upsert_benchmark() {
+        let games = benchmark_size("BENCH_GAMES", 500_000);
+        let directory = fixture_directory("bulk-upsert");
+        let database_path = directory.join("igdb.db");
+        let baseline_path = directory.join("igdb.baseline.db");
+        create_database(&database_path);
+        create_database(&baseline_path);
+        let passes = [("fresh", 0), ("unchanged", 0), ("10% changed", 10)];
+        for (pass, renamed_every) in passes {
+            write_games_csv(&directory, games, |id| {
+                renamed_every > 0 && id % renamed_every == 0
+            });
+            let start = Instant::now();
+            import_with_search_triggers(&baseline_path, &directory);
+            let baseline = start.elapsed();
+            let start = Instant::now();
+            let summary = import(&database_path, &directory);
+            let elapsed = start.elapsed();
+            let changes = summary
+                .table_changes
+                .iter()
+                .find(|changes| changes.table == "games")
+                .unwrap();
+            println!(
+                "{} import of {} games in {:.2?} (baseline {:.2?}): {} added, {} updated",
+                pass, games, elapsed, baseline, changes.added, changes.updated
+            );
+        }
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn import_reports_added_updated_and_removed_rows() {
+        let directory = fixture_directory("import-changes");
+        let database_path = directory.join("igdb.db");
+        create_database(&database_path);
+        write_games_csv(&directory, 3, |_| false);
+        write_csv(
+            &directory,
+            "genres",
+            "id,name",
+            ["1,RPG", "2,Shooter", "3,Puzzle"]
+                .map(String::from)
+                .into_iter(),
+        );
+        import(&database_path, &directory);
+        write_csv(
+            &directory,
+            "genres",
+            "id,name",
+            ["1,RPG", "2,Shooter", "3,Puzzler", "4,Racing"]
+                .map(String::from)
+                .into_iter(),
+        );
+        write_csv(
+            &directory,
+            "genres_deleted",
+            "id",
+            ["1".to_string()].into_iter(),
+        );
+        let summary = import(&database_path, &directory);
+        let changes = |table: &str| {
+            let changes = summary
+                .table_changes
+                .iter()
+                .find(|changes| changes.table == table)
+                .unwrap();
+            (changes.added, changes.updated, changes.removed)
+        };
+        assert_eq!(changes("genres"), (1, 1, 1));
+        assert_eq!(changes("games"), (0, 0, 0));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn link_changes_count_as_updated_games() {
+        let directory = fixture_directory("link-changes");
+        let database_path = directory.join("igdb.db");
+        create_database(&database_path);
+        let write_games = |game_1_similar: &str| {
+            write_csv(
+                &directory,
+                "games",
+                "id,name,cover,websites,similar_games,category,version_parent,total_rating,platforms",
+                [
+                    format!("1,Game 1,,{{}},\"{{{}}}\",0,,,", game_1_similar),
+                    "2,Game 2,,{},{},0,,,".to_string(),
+                    "3,Game 3,,{},{99},0,,,".to_string(),
+                ]
+                .into_iter(),
+            );
+        };
+        write_games("2");
+        import(&database_path, &directory);
+        write_games("2,3");
+        let summary = import(&database_path, &directory);
+        let changes = summary
+            .table_changes
+            .iter()
+            .find(|changes| changes.table == "games")
+            .unwrap();
+        assert_eq!((changes.added, changes.updated), (0, 1));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn similar_games_can_link_to_games_in_later_batches() {
+        let directory = fixture_directory("similar-games");
+        let database_path = directory.join("igdb.db");
+        create_database(&database_path);
+        let games = IMPORT_BATCH_SIZE as i32 + 1;
+        write_csv(
+            &directory,
+            "games",
+            "id,name,cover,websites,similar_games,category,version_parent,total_rating,platforms",
+            (1..=games).map(|id| match id {
+                1 => format!("1,Game 1,,{{}},\"{{{},{}}}\",0,,,", games, games + 1),
+                _ => format!("{},Game {},,{{}},{{}},0,,,", id, id),
+            }),
+        );
+        import(&database_path, &directory);
+        let similar_games = Connection::open(&database_path)
+            .unwrap()
+            .prepare("SELECT similar_game_id FROM similar_games WHERE game_id = 1")
+            .unwrap()
+            .query_map([], |row| row.get::<_, i32>(0))
+            .unwrap()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(similar_games, vec![games]);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[derive(Clone, Copy)]
+    enum Reply {
+        Honour,
+        Truncate,
+        IgnoreRange,
+        NotSatisfiable,
+    }
+
+    const TEST_ETAG: &str = "\"v1\"";
+
+    /// Serves `body` once per reply and records the `Range` header of every request it gets.
+    fn serve(
+        body: Vec<u8>,
+        replies: Vec<Reply>,
+    ) -> (String, std::thread::JoinHandle<Vec<Option<String>>>) {
+        use std::io::{BufRead, BufReader};
+        use std::net::TcpListener;
+
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let url = format!("http://{}/games.csv", listener.local_addr().unwrap());
+        let server = std::thread::spawn(move || {
+            let mut ranges = Vec::new();
+            for reply in replies {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut range_start = None;
+                let mut if_range = None;
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    let line = line.trim_end();
+                    if line.is_empty() {
+                        break;
+                    }
+                    let (header, value) = line.split_once(':').unwrap_or((line, ""));
+                    match header.to_ascii_lowercase().as_str() {
+                        "range" => range_start = Some(value.trim().to_string()),
+                        "if-range" => if_range = Some(value.trim().to_string()),
+                        _ => {}
+                    }
+                }
+                let start: Option<usize> = range_start.as_ref().map(|range| {
+                    let range = range.strip_prefix("bytes=").unwrap();
+                    range.trim_end_matches('-').parse().unwrap()
+                });
+                ranges.push(range_start);
+                let validated = if_range.as_deref().unwrap_or(TEST_ETAG) == TEST_ETAG;
+                let (status, extra_headers, content) = match (reply, start) {
+                    (Reply::NotSatisfiable, _) => (
+                        "416 Range Not Satisfiable",
+                        format!("Content-Range: bytes */{}\r\n", body.len()),
+                        &body[..0],
+                    ),
+                    (Reply::Honour, Some(start)) if validated => (
+                        "206 Partial Content",
+                        format!(
+                            "Content-Range: bytes {}-{}/{}\r\n",
+                            start,
+                            body.len() - 1,
+                            body.len()
+                        ),
+                        &body[start..],
+                    ),
+                    _ => ("200 OK", String::new(), &body[..]),
+                };
+                let etag = match reply {
+                    Reply::IgnoreRange => String::new(),
+                    _ => format!("ETag: {}\r\n", TEST_ETAG),
+                };
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\n{}{}Connection: close\r\n\r\n",
+                    status,
+                    content.len(),
+                    etag,
+                    extra_headers
+                )
+                .unwrap();
+                let sent = match reply {
+                    Reply::Truncate => &content[..content.len() / 2],
+                    _ => content,
+                };
+                stream.write_all(sent).unwrap();
+            }
+            ranges
+        });
+        (url, server)
+    }
+
+    fn csv_body() -> Vec<u8> {
+        let mut body = b"id,name\n".to_vec();
+        for id in 1..=20_000 {
+            body.extend(format!("{},Genre {}\n", id, id).as_bytes());
+        }
+        body
+    }
+
+    fn sha256(bytes: &[u8]) -> String {
+        format!("{:x}", Sha256::digest(bytes))
+    }
+
+    fn dump_info(url: &str, version: &str) -> DumpInfo<'static> {
+        DumpInfo {
+            name: "games",
+            url: url.to_string(),
+            version: version.to_string(),
+            checksum: None,
+            delta_since: None,
+            deleted_url: None,
+            deleted_checksum: None,
+            compression: None,
+        }
+    }
+
+    fn download(info: &DumpInfo<'_>, checksum: &str, directory: &Path) -> Result<(), Error> {
+        tauri::async_runtime::block_on(download_csv(
+            &|_| Ok(()),
+            info,
+            &info.url,
+            Some(checksum),
+            directory,
+            "games",
+        ))
+    }
+
+    fn file_names(directory: &Path) -> Vec<String> {
+        let mut names: Vec<String> = fs::read_dir(directory)
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn truncated_download_is_resumed() {
+        let body = csv_body();
+        let directory = fixture_directory("download-truncated");
+        let (url, server) = serve(body.clone(), vec![Reply::Truncate, Reply::Honour]);
+        download(&dump_info(&url, "1"), &sha256(&body), &directory).unwrap();
+        let ranges = server.join().unwrap();
+        assert_eq!(
+            ranges,
+            vec![None, Some(format!("bytes={}-", body.len() / 2))]
+        );
+        assert_eq!(fs::read(directory.join("games.csv")).unwrap(), body);
+        assert_eq!(file_names(&directory), vec!["games.csv"]);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn download_restarts_when_the_server_ignores_the_range() {
+        let body = csv_body();
+        let directory = fixture_directory("download-ignored-range");
+        let (url, server) = serve(body.clone(), vec![Reply::IgnoreRange]);
+        let partial_path =
+            partial_download_path(&directory, "games", &url, "1", Some(&sha256(&body)));
+        fs::write(&partial_path, b"stale bytes").unwrap();
+        download(&dump_info(&url, "1"), &sha256(&body), &directory).unwrap();
+        assert_eq!(server.join().unwrap(), vec![Some("bytes=11-".to_string())]);
+        assert_eq!(fs::read(directory.join("games.csv")).unwrap(), body);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn partial_download_of_another_version_is_not_resumed() {
+        let body = csv_body();
+        let directory = fixture_directory("download-other-version");
+        let (url, server) = serve(body.clone(), vec![Reply::Honour]);
+        let stale_path = partial_download_path(&directory, "games", &url, "1", None);
+        fs::write(&stale_path, &body[..100]).unwrap();
+        download(&dump_info(&url, "2"), &sha256(&body), &directory).unwrap();
+        assert_eq!(server.join().unwrap(), vec![None]);
+        assert_eq!(fs::read(directory.join("games.csv")).unwrap(), body);
+        assert_eq!(file_names(&directory), vec!["games.csv"]);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn unsatisfiable_range_restarts_the_download() {
+        let body = csv_body();
+        let directory = fixture_directory("download-not-satisfiable");
+        let (url, server) = serve(body.clone(), vec![Reply::NotSatisfiable, Reply::Honour]);
+        let partial_path =
+            partial_download_path(&directory, "games", &url, "1", Some(&sha256(&body)));
+        fs::write(&partial_path, [&body[..], b"junk"].concat()).unwrap();
+        download(&dump_info(&url, "1"), &sha256(&body), &directory).unwrap();
+        assert_eq!(
+            server.join().unwrap(),
+            vec![Some(format!("bytes={}-", body.len() + 4)), None]
+        );
+        assert_eq!(fs::read(directory.join("games.csv")).unwrap(), body);
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn corrupted_download_is_discarded() {
+        let body = csv_body();
+        let mut corrupted = body.clone();
+        corrupted[body.len() / 2] = b'#';
+        let directory = fixture_directory("download-corrupted");
+        fs::write(directory.join("games.csv"), b"id,name\n").unwrap();
+        let (url, server) = serve(corrupted, vec![Reply::Honour]);
+        let error = download(&dump_info(&url, "1"), &sha256(&body), &directory).unwrap_err();
+        server.join().unwrap();
+        assert!(error.to_string().contains("Checksum mismatch"));
+        assert_eq!(file_names(&directory), vec!["games.csv"]);
+        assert_eq!(fs::read(directory.join("games.csv")).unwrap(), b"id,name\n");
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
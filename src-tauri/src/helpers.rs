@@ -7,7 +7,14 @@ use std::{
 
 use tauri::Manager;
 
-use crate::{Error, ProcessMonitoringSettings, UserSettings};
+use crate::{
+    covers::DEFAULT_COVER_CDN,
+    dump_updates::request_notification_permission,
+    dumps::{RowErrorPolicy, DEFAULT_DUMP_SOURCE},
+    igdb::{DEFAULT_CATEGORIES, DEFAULT_EXCLUDED_PLATFORMS},
+    reviews::DEFAULT_REVIEW_DIMENSIONS,
+    ContentFilterSettings, DumpUpdateSettings, Error, ProcessMonitoringSettings, UserSettings,
+};
 
 #[derive(serde::Deserialize, Debug)]
 #[allow(dead_code)]
@@ -72,6 +79,92 @@ pub fn get_user_settings(app_handle: tauri::AppHandle) -> Result<UserSettings, E
             .remove("new")
             .and_then(|v| v.as_bool())
             .unwrap_or(true),
+        review_dimensions: settings_map
+            .remove("review_dimensions")
+            .and_then(|v| {
+                v.as_array().map(|dimensions| {
+                    dimensions
+                        .iter()
+                        .filter_map(|d| d.as_str().map(String::from))
+                        .collect()
+                })
+            })
+            .unwrap_or_else(|| {
+                DEFAULT_REVIEW_DIMENSIONS
+                    .iter()
+                    .map(|d| d.to_string())
+                    .collect()
+            }),
+        dump_source: settings_map
+            .remove("dump_source")
+            .and_then(|v| v.as_str().map(String::from))
+            .unwrap_or_else(|| DEFAULT_DUMP_SOURCE.to_string()),
+        dump_updates: {
+            let dump_updates_map = settings_map
+                .remove("dump_updates")
+                .and_then(|v| v.as_table().cloned())
+                .unwrap_or_default();
+            DumpUpdateSettings {
+                automatic: dump_updates_map
+                    .get("automatic")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+                interval_hours: dump_updates_map
+                    .get("interval_hours")
+                    .and_then(|v| v.as_integer())
+                    .unwrap_or(24)
+                    .max(1) as u32,
+                pause_on_metered: dump_updates_map
+                    .get("pause_on_metered")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(true),
+                row_error_policy: dump_updates_map
+                    .get("row_error_policy")
+                    .and_then(|v| v.clone().try_into().ok())
+                    .unwrap_or(RowErrorPolicy::Skip),
+            }
+        },
+        cover_cdn: settings_map
+            .remove("cover_cdn")
+            .and_then(|v| v.as_str().map(String::from))
+            .unwrap_or_else(|| DEFAULT_COVER_CDN.to_string()),
+        content_filter: {
+            let content_filter_map = settings_map
+                .remove("content_filter")
+                .and_then(|v| v.as_table().cloned())
+                .unwrap_or_default();
+            ContentFilterSettings {
+                categories: content_filter_map
+                    .get("categories")
+                    .and_then(|v| v.as_array())
+                    .map(|categories| {
+                        categories
+                            .iter()
+                            .filter_map(|c| c.as_integer().map(|c| c as i32))
+                            .collect()
+                    })
+                    .unwrap_or_else(|| DEFAULT_CATEGORIES.to_vec()),
+                excluded_platforms: content_filter_map
+                    .get("excluded_platforms")
+                    .and_then(|v| v.as_array())
+                    .map(|platforms| {
+                        platforms
+                            .iter()
+                            .filter_map(|p| p.as_str().map(String::from))
+                            .collect()
+                    })
+                    .unwrap_or_else(|| {
+                        DEFAULT_EXCLUDED_PLATFORMS
+                            .iter()
+                            .map(|p| p.to_string())
+                            .collect()
+                    }),
+                include_versions: content_filter_map
+                    .get("include_versions")
+                    .and_then(|v| v.as_bool())
+                    .unwrap_or(false),
+            }
+        },
     };
 
     Ok(user_settings)
@@ -82,6 +175,9 @@ pub fn save_user_settings(
     user_settings: UserSettings,
     app_handle: tauri::AppHandle,
 ) -> Result<UserSettings, Error> {
+    if user_settings.dump_updates.automatic {
+        request_notification_permission(&app_handle);
+    }
     let config_path = app_handle.path().config_dir().unwrap();
     let settings_str = toml::to_string(&user_settings)?;
     fs::write(
@@ -101,6 +197,14 @@ pub fn create_dir_if_not_exists(path: &Path) -> Result<(), std::io::Error> {
     }
 }
 
+pub fn remove_file_if_exists(path: &Path) -> Result<(), Error> {
+    match fs::remove_file(path) {
+        Ok(_) => Ok(()),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
 pub fn get_app_data_directory(app_handle: &tauri::AppHandle) -> Result<PathBuf, Error> {
     let dir = app_handle.path().data_dir()?;
     Ok(dir.join("game-chronicle"))
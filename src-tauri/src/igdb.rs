@@ -1,6 +1,10 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::NaiveDate;
+use rusqlite::ToSql;
 use tauri::State;
 
-use crate::{DatabaseConnections, Error};
+use crate::{helpers::get_user_settings, ContentFilterSettings, DatabaseConnections, Error};
 
 #[derive(serde::Serialize, Debug, serde::Deserialize)]
 pub struct Game {
@@ -23,6 +27,51 @@ pub struct Game {
         deserialize_with = "deserialize_list"
     )]
     pub platform_ids: Option<Vec<i32>>,
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub genres: Option<Vec<i32>>,
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub themes: Option<Vec<i32>>,
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub game_modes: Option<Vec<i32>>,
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub franchises: Option<Vec<i32>>,
+    #[serde(default, deserialize_with = "deserialize_list")]
+    pub collections: Option<Vec<i32>>,
+    #[serde(default)]
+    pub summary: Option<String>,
+    #[serde(default)]
+    pub storyline: Option<String>,
+}
+
+/// Genres, themes, game modes, franchises, collections and companies all share this shape.
+#[derive(serde::Serialize, Debug, serde::Deserialize)]
+pub struct NamedEntity {
+    pub id: i32,
+    pub name: String,
+}
+
+#[derive(serde::Serialize, Debug, serde::Deserialize)]
+pub struct InvolvedCompany {
+    pub id: i32,
+    #[serde(rename(deserialize = "game"))]
+    pub game_id: i32,
+    #[serde(rename(deserialize = "company"))]
+    pub company_id: i32,
+    #[serde(deserialize_with = "deserialize_bool")]
+    pub developer: bool,
+    #[serde(deserialize_with = "deserialize_bool")]
+    pub publisher: bool,
+}
+
+#[derive(serde::Serialize, Debug, serde::Deserialize)]
+pub struct GameReleaseDate {
+    pub id: i32,
+    #[serde(rename(deserialize = "game"))]
+    pub game_id: i32,
+    #[serde(rename(deserialize = "platform"))]
+    pub platform_id: Option<i32>,
+    pub date: Option<i64>,
+    pub region: Option<i32>,
 }
 
 #[derive(serde::Serialize, Debug, serde::Deserialize)]
@@ -44,6 +93,47 @@ pub struct Platform {
     pub category: Option<i32>,
 }
 
+#[derive(serde::Serialize, Debug, serde::Deserialize)]
+pub struct GameTimeToBeat {
+    pub id: i32,
+    pub game_id: i32,
+    pub hastily: Option<i32>,
+    pub normally: Option<i32>,
+    pub completely: Option<i32>,
+}
+
+#[derive(serde::Serialize, Debug, serde::Deserialize, Clone)]
+pub struct TimeToBeat {
+    pub hastily: Option<i32>,
+    pub normally: Option<i32>,
+    pub completely: Option<i32>,
+}
+
+#[derive(serde::Serialize, Debug, serde::Deserialize)]
+pub struct AlternativeName {
+    pub id: i32,
+    #[serde(rename(deserialize = "game"))]
+    pub game_id: i32,
+    pub name: String,
+    pub comment: Option<String>,
+}
+
+#[derive(serde::Serialize, Debug, serde::Deserialize)]
+pub struct GameLocalization {
+    pub id: i32,
+    #[serde(rename(deserialize = "game"))]
+    pub game_id: i32,
+    pub name: String,
+    pub region: Option<i32>,
+}
+
+#[derive(serde::Serialize, Debug, serde::Deserialize, Clone)]
+pub struct ReleaseDate {
+    pub date: Option<i64>,
+    pub platform: Option<String>,
+    pub region: Option<i32>,
+}
+
 #[derive(serde::Serialize, Debug, serde::Deserialize, Clone)]
 pub struct GameInfo {
     pub id: i32,
@@ -54,6 +144,28 @@ pub struct GameInfo {
     pub category: i32,
     pub version_parent: Option<i32>,
     pub total_rating: Option<f32>,
+    pub time_to_beat: Option<TimeToBeat>,
+    pub summary: Option<String>,
+    pub storyline: Option<String>,
+    pub genres: Vec<String>,
+    pub themes: Vec<String>,
+    pub game_modes: Vec<String>,
+    pub franchises: Vec<String>,
+    pub collections: Vec<String>,
+    pub developers: Vec<String>,
+    pub publishers: Vec<String>,
+    pub release_dates: Vec<ReleaseDate>,
+}
+
+/// A search hit together with the name it was found by, which may be an alternative or
+/// localized name rather than the title.
+#[derive(serde::Serialize, Debug, serde::Deserialize)]
+pub struct GameSearchResult {
+    #[serde(flatten)]
+    pub game: GameInfo,
+    pub matched_name: String,
+    pub matched_by: String,
+    pub score: f64,
 }
 
 #[derive(serde::Serialize, Debug, serde::Deserialize)]
@@ -73,14 +185,43 @@ where
     if s.is_empty() {
         return Ok(None);
     }
-    Ok(Some(
-        s.split(',')
-            .map(|item| match item.trim().parse::<i32>() {
-                Ok(id) => id,
-                Err(_) => 0,
+    s.split(',')
+        .map(|item| {
+            item.trim().parse::<i32>().map_err(|_| {
+                serde::de::Error::custom(format!("'{}' is not a valid id", item.trim()))
             })
-            .collect(),
-    ))
+        })
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
+fn deserialize_bool<'de, D>(deserializer: D) -> Result<bool, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    let s: String = serde::Deserialize::deserialize(deserializer)?;
+    match s.trim() {
+        "t" | "true" | "1" => Ok(true),
+        "f" | "false" | "0" | "" => Ok(false),
+        other => Err(serde::de::Error::custom(format!(
+            "'{}' is not a boolean",
+            other
+        ))),
+    }
+}
+
+/// Reads a column built with `json_group_array`.
+fn json_column<T: serde::de::DeserializeOwned>(
+    row: &rusqlite::Row,
+    column: &str,
+) -> Result<Vec<T>, rusqlite::Error> {
+    let json: Option<String> = row.get(column)?;
+    match json {
+        Some(json) => serde_json::from_str(&json).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(0, rusqlite::types::Type::Text, Box::new(e))
+        }),
+        None => Ok(vec![]),
+    }
 }
 
 fn game_info_from_row(row: &rusqlite::Row) -> Result<GameInfo, rusqlite::Error> {
@@ -99,6 +240,14 @@ fn game_info_from_row(row: &rusqlite::Row) -> Result<GameInfo, rusqlite::Error>
         ),
         None => None,
     };
+    let time_to_beat = match row.get::<&str, Option<i32>>("time_to_beat_id")? {
+        Some(_) => Some(TimeToBeat {
+            hastily: row.get("hastily")?,
+            normally: row.get("normally")?,
+            completely: row.get("completely")?,
+        }),
+        None => None,
+    };
     Ok(GameInfo {
         id: row.get("id")?,
         title: row.get("name")?,
@@ -108,85 +257,562 @@ fn game_info_from_row(row: &rusqlite::Row) -> Result<GameInfo, rusqlite::Error>
         category: row.get("category")?,
         version_parent: row.get("version_parent")?,
         total_rating: row.get("total_rating")?,
+        time_to_beat,
+        summary: row.get("summary")?,
+        storyline: row.get("storyline")?,
+        genres: json_column(row, "genres")?,
+        themes: json_column(row, "themes")?,
+        game_modes: json_column(row, "game_modes")?,
+        franchises: json_column(row, "franchises")?,
+        collections: json_column(row, "collections")?,
+        developers: json_column(row, "developers")?,
+        publishers: json_column(row, "publishers")?,
+        release_dates: json_column(row, "release_dates")?,
     })
 }
 
 fn game_info_columns() -> &'static str {
-    "g.id, g.name, c.image_id, GROUP_CONCAT(w.url, ',') websites, GROUP_CONCAT(sg.similar_game_id, ',') similar_game_ids, g.category, g.version_parent, total_rating FROM games g LEFT JOIN covers c ON g.cover_id = c.id LEFT JOIN game_websites gw ON g.id = gw.game_id LEFT JOIN websites w ON gw.website_id = w.id LEFT JOIN similar_games sg ON sg.game_id = g.id LEFT JOIN game_platforms gp ON g.id = gp.game_id LEFT JOIN platforms p ON p.id = gp.platform_id LEFT JOIN popularity_primitives pp ON g.id = pp.game_id"
+    "g.id, g.name, c.image_id, GROUP_CONCAT(w.url, ',') websites, GROUP_CONCAT(sg.similar_game_id, ',') similar_game_ids, g.category, g.version_parent, total_rating, ttb.id time_to_beat_id, ttb.hastily, ttb.normally, ttb.completely, g.summary, g.storyline, (SELECT json_group_array(ge.name) FROM game_genres gg JOIN genres ge ON ge.id = gg.genre_id WHERE gg.game_id = g.id) genres, (SELECT json_group_array(t.name) FROM game_themes gt JOIN themes t ON t.id = gt.theme_id WHERE gt.game_id = g.id) themes, (SELECT json_group_array(gm.name) FROM game_game_modes ggm JOIN game_modes gm ON gm.id = ggm.game_mode_id WHERE ggm.game_id = g.id) game_modes, (SELECT json_group_array(f.name) FROM game_franchises gf JOIN franchises f ON f.id = gf.franchise_id WHERE gf.game_id = g.id) franchises, (SELECT json_group_array(co.name) FROM game_collections gc JOIN collections co ON co.id = gc.collection_id WHERE gc.game_id = g.id) collections, (SELECT json_group_array(cm.name) FROM involved_companies ic JOIN companies cm ON cm.id = ic.company_id WHERE ic.game_id = g.id AND ic.developer) developers, (SELECT json_group_array(cm.name) FROM involved_companies ic JOIN companies cm ON cm.id = ic.company_id WHERE ic.game_id = g.id AND ic.publisher) publishers, (SELECT json_group_array(json_object('date', rd.date, 'platform', rp.name, 'region', rd.region)) FROM (SELECT * FROM release_dates WHERE game_id = g.id ORDER BY date) rd LEFT JOIN platforms rp ON rp.id = rd.platform_id) release_dates FROM games g LEFT JOIN covers c ON g.cover_id = c.id LEFT JOIN game_time_to_beats ttb ON ttb.game_id = g.id LEFT JOIN game_websites gw ON g.id = gw.game_id LEFT JOIN websites w ON gw.website_id = w.id LEFT JOIN similar_games sg ON sg.game_id = g.id"
+}
+
+/// Categories making up the catalogue by default: main games, standalone expansions, remakes
+/// and remasters.
+pub const DEFAULT_CATEGORIES: [i32; 4] = [0, 4, 8, 9];
+pub const DEFAULT_EXCLUDED_PLATFORMS: [&str; 2] = ["Android", "iOS"];
+
+type NamedParams = Vec<(&'static str, Box<dyn ToSql>)>;
+
+/// Adds the conditions on `g` keeping only the games `content_filter` lets into the catalogue.
+/// Games released only on excluded platforms are left out.
+fn push_content_conditions(
+    content_filter: &ContentFilterSettings,
+    conditions: &mut Vec<&'static str>,
+    params: &mut NamedParams,
+) -> Result<(), Error> {
+    if !content_filter.categories.is_empty() {
+        conditions.push("g.category IN (SELECT value FROM json_each(:content_categories))");
+        params.push((
+            ":content_categories",
+            Box::new(serde_json::to_string(&content_filter.categories)?),
+        ));
+    }
+    if !content_filter.excluded_platforms.is_empty() {
+        conditions.push("EXISTS (SELECT 1 FROM game_platforms cgp JOIN platforms cp ON cp.id = cgp.platform_id WHERE cgp.game_id = g.id AND cp.name NOT IN (SELECT value FROM json_each(:excluded_platforms)))");
+        params.push((
+            ":excluded_platforms",
+            Box::new(serde_json::to_string(&content_filter.excluded_platforms)?),
+        ));
+    }
+    if !content_filter.include_versions {
+        conditions.push("g.version_parent IS NULL");
+    }
+    Ok(())
+}
+
+fn where_clause(conditions: &[&str]) -> String {
+    if conditions.is_empty() {
+        String::new()
+    } else {
+        format!(" WHERE {}", conditions.join(" AND "))
+    }
+}
+
+fn named_params(params: &NamedParams) -> Vec<(&str, &dyn ToSql)> {
+    params
+        .iter()
+        .map(|(name, value)| (*name, value.as_ref()))
+        .collect()
+}
+
+/// Catalogue games with the given ids, in the same order.
+pub fn games_by_id(
+    connections: &DatabaseConnections,
+    content_filter: &ContentFilterSettings,
+    game_ids: &[i32],
+) -> Result<Vec<GameInfo>, Error> {
+    if game_ids.is_empty() {
+        return Ok(vec![]);
+    }
+    let mut conditions = vec!["g.id IN (SELECT value FROM json_each(:game_ids))"];
+    let mut params: NamedParams = vec![(":game_ids", Box::new(serde_json::to_string(game_ids)?))];
+    push_content_conditions(content_filter, &mut conditions, &mut params)?;
+    let conn = connections.igdb_conn.lock().unwrap();
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}{} GROUP BY g.id",
+        game_info_columns(),
+        where_clause(&conditions)
+    ))?;
+    let mut games: HashMap<i32, GameInfo> = stmt
+        .query_map(named_params(&params).as_slice(), game_info_from_row)?
+        .map(|game| game.map(|game| (game.id, game)))
+        .collect::<Result<_, _>>()?;
+    Ok(game_ids
+        .iter()
+        .filter_map(|game_id| games.remove(game_id))
+        .collect())
 }
 
 #[tauri::command]
 pub fn get_games_by_id(
+    app_handle: tauri::AppHandle,
     state: State<'_, DatabaseConnections>,
     game_ids: Vec<i32>,
 ) -> Result<Vec<GameInfo>, Error> {
-    if game_ids.is_empty() {
-        return Ok(vec![]);
+    let content_filter = get_user_settings(app_handle)?.content_filter;
+    games_by_id(&state, &content_filter, &game_ids)
+}
+
+/// Narrows down search results and popular games. Empty lists and unset values don't filter
+/// anything. Release years are compared against a game's first release.
+#[derive(Debug, Default, serde::Deserialize)]
+#[serde(default)]
+pub struct GameFilter {
+    pub platform_ids: Vec<i32>,
+    pub categories: Vec<i32>,
+    pub release_year_from: Option<i32>,
+    pub release_year_to: Option<i32>,
+    pub min_total_rating: Option<f32>,
+    pub has_cover: bool,
+    pub not_in_journal: bool,
+}
+
+/// One page of games, counted from 1, and how many games there are across all pages.
+#[derive(Debug, serde::Serialize)]
+pub struct GamePage<T> {
+    pub games: Vec<T>,
+    pub total: usize,
+}
+
+fn page_offset(page: u32, page_size: u32) -> u64 {
+    u64::from(page.saturating_sub(1)) * u64::from(page_size)
+}
+
+fn year_start(year: i32) -> Result<i64, Error> {
+    NaiveDate::from_ymd_opt(year, 1, 1)
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|date| date.and_utc().timestamp())
+        .ok_or_else(|| Error::from(format!("{} is not a valid year", year)))
+}
+
+fn logged_game_ids(connections: &DatabaseConnections) -> Result<Vec<i32>, Error> {
+    let conn = connections.logs_conn.lock().unwrap();
+    let game_ids = conn
+        .prepare("SELECT DISTINCT game_id FROM logs")?
+        .query_map([], |row| row.get(0))?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(game_ids)
+}
+
+/// The conditions on `g` for the catalogue games that pass `filter`, with their named parameters.
+fn game_filter_conditions(
+    connections: &DatabaseConnections,
+    content_filter: &ContentFilterSettings,
+    filter: &GameFilter,
+) -> Result<(Vec<&'static str>, NamedParams), Error> {
+    let mut conditions = vec![];
+    let mut params: NamedParams = vec![];
+    push_content_conditions(content_filter, &mut conditions, &mut params)?;
+    if !filter.platform_ids.is_empty() {
+        conditions.push("EXISTS (SELECT 1 FROM game_platforms fp WHERE fp.game_id = g.id AND fp.platform_id IN (SELECT value FROM json_each(:platform_ids)))");
+        params.push((
+            ":platform_ids",
+            Box::new(serde_json::to_string(&filter.platform_ids)?),
+        ));
     }
-    let conn = state.igdb_conn.lock().unwrap();
-    let query = format!(
-        "SELECT {} WHERE g.id IN ({}) AND g.category IN (0, 4, 8, 9) AND p.name NOT IN ('Android', 'iOS') AND g.version_parent IS NULL GROUP BY g.id;",
-        game_info_columns(), game_ids
-            .iter()
-            .map(|id| id.to_string())
-            .collect::<Vec<String>>()
-            .join(",")
+    if !filter.categories.is_empty() {
+        conditions.push("g.category IN (SELECT value FROM json_each(:categories))");
+        params.push((
+            ":categories",
+            Box::new(serde_json::to_string(&filter.categories)?),
+        ));
+    }
+    if let Some(year) = filter.release_year_from {
+        conditions.push(
+            "(SELECT MIN(rd.date) FROM release_dates rd WHERE rd.game_id = g.id) >= :released_from",
+        );
+        params.push((":released_from", Box::new(year_start(year)?)));
+    }
+    if let Some(year) = filter.release_year_to {
+        conditions.push("(SELECT MIN(rd.date) FROM release_dates rd WHERE rd.game_id = g.id) < :released_before");
+        params.push((":released_before", Box::new(year_start(year + 1)?)));
+    }
+    if let Some(min_total_rating) = filter.min_total_rating {
+        conditions.push("g.total_rating >= :min_total_rating");
+        params.push((":min_total_rating", Box::new(min_total_rating)));
+    }
+    if filter.has_cover {
+        conditions.push("g.cover_id IS NOT NULL");
+    }
+    if filter.not_in_journal {
+        conditions.push("g.id NOT IN (SELECT value FROM json_each(:logged_game_ids))");
+        params.push((
+            ":logged_game_ids",
+            Box::new(serde_json::to_string(&logged_game_ids(connections)?)?),
+        ));
+    }
+    Ok((conditions, params))
+}
+
+fn filtered_game_ids_query(
+    connections: &DatabaseConnections,
+    content_filter: &ContentFilterSettings,
+    filter: &GameFilter,
+) -> Result<(String, NamedParams), Error> {
+    let (conditions, params) = game_filter_conditions(connections, content_filter, filter)?;
+    let query = format!("SELECT g.id FROM games g{}", where_clause(&conditions));
+    Ok((query, params))
+}
+
+fn passes_conditions(game_id_column: &str, conditions: &[&str]) -> String {
+    let mut exists = format!(
+        "EXISTS (SELECT 1 FROM games g WHERE g.id = {}",
+        game_id_column
     );
-    let mut stmt = conn.prepare(&query)?;
-    let games = stmt
-        .query_map([], game_info_from_row)?
-        .collect::<Result<Vec<_>, _>>()?;
-    Ok(games)
+    for condition in conditions {
+        exists.push_str(" AND ");
+        exists.push_str(condition);
+    }
+    exists.push(')');
+    exists
 }
 
 #[tauri::command]
 pub async fn get_popular_games(
+    app_handle: tauri::AppHandle,
     state: State<'_, DatabaseConnections>,
-    amount: i32,
-) -> Result<Vec<GameInfo>, Error> {
+    filter: GameFilter,
+    page: u32,
+    page_size: u32,
+) -> Result<GamePage<GameInfo>, Error> {
+    let content_filter = get_user_settings(app_handle)?.content_filter;
+    let (ids_query, mut params) = filtered_game_ids_query(&state, &content_filter, &filter)?;
+    let (game_ids, total) = {
+        let conn = state.igdb_conn.lock().unwrap();
+        let total = conn.query_row(
+            &format!("SELECT COUNT(*) FROM ({})", ids_query),
+            named_params(&params).as_slice(),
+            |row| row.get::<_, usize>(0),
+        )?;
+        params.push((":limit", Box::new(page_size)));
+        params.push((":offset", Box::new(page_offset(page, page_size))));
+        let game_ids = conn
+            .prepare(&format!("SELECT f.id FROM ({}) f LEFT JOIN popularity_primitives pp ON pp.game_id = f.id GROUP BY f.id ORDER BY MAX(pp.value) DESC, f.id LIMIT :limit OFFSET :offset", ids_query))?
+            .query_map(named_params(&params).as_slice(), |row| row.get::<_, i32>(0))?
+            .collect::<Result<Vec<_>, _>>()?;
+        (game_ids, total)
+    };
+    Ok(GamePage {
+        games: games_by_id(&state, &content_filter, &game_ids)?,
+        total,
+    })
+}
+
+/// Platforms to filter by, sorted by name.
+#[tauri::command]
+pub fn get_platforms(state: State<'_, DatabaseConnections>) -> Result<Vec<NamedEntity>, Error> {
     let conn = state.igdb_conn.lock().unwrap();
-    let games = conn.prepare(&format!("SELECT {} WHERE g.category IN (0, 4, 8, 9) AND p.name NOT IN ('Android', 'iOS') AND g.version_parent IS NULL GROUP BY g.id ORDER BY pp.value DESC LIMIT ?;", game_info_columns()).to_string())?
-        .query_map([amount], game_info_from_row)?
+    let platforms = conn
+        .prepare("SELECT id, name FROM platforms ORDER BY name")?
+        .query_map([], |row| {
+            Ok(NamedEntity {
+                id: row.get(0)?,
+                name: row.get(1)?,
+            })
+        })?
         .collect::<Result<Vec<_>, _>>()?;
-    Ok(games)
+    Ok(platforms)
+}
+
+/// Misspelled searches only rank this many of the closest trigram matches. The number is fixed so
+/// every page is cut from the same ranking.
+const FUZZY_CANDIDATE_LIMIT: u64 = 200;
+/// How close a misspelled name has to be, from 0 to 1, to count as a match.
+const MIN_FUZZY_SIMILARITY: f64 = 0.6;
+/// Share of the score that comes from popularity rather than from how well the name matched.
+const POPULARITY_WEIGHT: f64 = 0.2;
+
+struct SearchMatch {
+    game_id: i32,
+    name: String,
+    matched_by: String,
+    /// How well the name matched, from 0 to 1.
+    relevance: f64,
+}
+
+/// Splitting the way the FTS tokenizer does drops anything FTS5 would read as query syntax.
+fn search_terms(search_query: &str) -> Vec<String> {
+    search_query
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(|term| term.to_lowercase())
+        .collect()
+}
+
+fn fts_prefix_query(terms: &[String]) -> String {
+    terms
+        .iter()
+        .map(|term| format!("\"{}\"*", term))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn trigram_query(terms: &[String]) -> Option<String> {
+    let mut trigrams = vec![];
+    for term in terms {
+        let chars = term.chars().collect::<Vec<_>>();
+        for window in chars.windows(3) {
+            let trigram = format!("\"{}\"", window.iter().collect::<String>());
+            if !trigrams.contains(&trigram) {
+                trigrams.push(trigram);
+            }
+        }
+    }
+    (!trigrams.is_empty()).then(|| trigrams.join(" OR "))
+}
+
+/// Edit distance where swapping two neighbouring characters, a common typo, counts as one edit.
+fn edit_distance(a: &[char], b: &[char]) -> usize {
+    let mut before_previous = vec![0; b.len() + 1];
+    let mut previous = (0..=b.len()).collect::<Vec<_>>();
+    for i in 0..a.len() {
+        let mut current = vec![i + 1; b.len() + 1];
+        for j in 0..b.len() {
+            let substitution = previous[j] + usize::from(a[i] != b[j]);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            if i > 0 && j > 0 && a[i] == b[j - 1] && a[i - 1] == b[j] {
+                current[j + 1] = current[j + 1].min(before_previous[j - 1] + 1);
+            }
+        }
+        before_previous = std::mem::replace(&mut previous, current);
+    }
+    previous[b.len()]
+}
+
+/// Words are also compared cut to the term's length, so a misspelled prefix still matches.
+fn name_similarity(terms: &[String], name: &str) -> f64 {
+    let words = search_terms(name)
+        .into_iter()
+        .map(|word| word.chars().collect::<Vec<_>>())
+        .collect::<Vec<_>>();
+    let total: f64 = terms
+        .iter()
+        .map(|term| {
+            let term = term.chars().collect::<Vec<_>>();
+            words
+                .iter()
+                .map(|word| {
+                    let prefix = &word[..word.len().min(term.len())];
+                    let distance = edit_distance(&term, word).min(edit_distance(&term, prefix));
+                    let length = term.len().max(prefix.len());
+                    1.0 - distance as f64 / length as f64
+                })
+                .fold(0.0, f64::max)
+        })
+        .sum();
+    total / terms.len() as f64
+}
+
+fn find_text_matches(
+    conn: &rusqlite::Connection,
+    terms: &[String],
+    conditions: &[&str],
+    params: &NamedParams,
+) -> Result<Vec<SearchMatch>, Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT rowid, name, 'name', bm25(games_fts) FROM games_fts WHERE games_fts MATCH :query AND {} UNION ALL SELECT an.game_id, an.name, 'alternative_name', bm25(alternative_names_fts) FROM alternative_names_fts JOIN alternative_names an ON an.id = alternative_names_fts.rowid WHERE alternative_names_fts MATCH :query AND {} UNION ALL SELECT gl.game_id, gl.name, 'localization', bm25(game_localizations_fts) FROM game_localizations_fts JOIN game_localizations gl ON gl.id = game_localizations_fts.rowid WHERE game_localizations_fts MATCH :query AND {}",
+        passes_conditions("games_fts.rowid", conditions),
+        passes_conditions("an.game_id", conditions),
+        passes_conditions("gl.game_id", conditions)
+    ))?;
+    let query = fts_prefix_query(terms);
+    let mut params = named_params(params);
+    params.push((":query", &query));
+    let mut matches = stmt
+        .query_map(params.as_slice(), |row| {
+            Ok(SearchMatch {
+                game_id: row.get(0)?,
+                name: row.get(1)?,
+                matched_by: row.get(2)?,
+                // bm25 is negative, lower meaning a better match.
+                relevance: -row.get::<_, f64>(3)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    let best = matches
+        .iter()
+        .map(|search_match| search_match.relevance)
+        .fold(0.0, f64::max);
+    if best > 0.0 {
+        for search_match in &mut matches {
+            search_match.relevance = (search_match.relevance / best).max(0.0);
+        }
+    }
+    Ok(matches)
+}
+
+fn find_fuzzy_matches(
+    conn: &rusqlite::Connection,
+    terms: &[String],
+    conditions: &[&str],
+    params: &NamedParams,
+) -> Result<Vec<SearchMatch>, Error> {
+    let Some(query) = trigram_query(terms) else {
+        return Ok(vec![]);
+    };
+    let mut stmt = conn.prepare(&format!(
+        "SELECT rowid, name FROM games_trigram WHERE games_trigram MATCH :query AND {} ORDER BY rank LIMIT :limit",
+        passes_conditions("games_trigram.rowid", conditions)
+    ))?;
+    let mut params = named_params(params);
+    params.push((":query", &query));
+    params.push((":limit", &FUZZY_CANDIDATE_LIMIT));
+    let candidates = stmt
+        .query_map(params.as_slice(), |row| {
+            Ok((row.get::<_, i32>(0)?, row.get::<_, String>(1)?))
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(candidates
+        .into_iter()
+        .filter_map(|(game_id, name)| {
+            let relevance = name_similarity(terms, &name);
+            (relevance >= MIN_FUZZY_SIMILARITY).then(|| SearchMatch {
+                game_id,
+                name,
+                matched_by: "name".to_string(),
+                relevance,
+            })
+        })
+        .collect())
+}
+
+/// Popularity is on a log scale relative to the most popular match, so a few huge titles don't
+/// drown out the rest.
+fn rank_matches(
+    conn: &rusqlite::Connection,
+    matches: Vec<SearchMatch>,
+) -> Result<Vec<(SearchMatch, f64)>, Error> {
+    let mut best_matches: HashMap<i32, SearchMatch> = HashMap::new();
+    for search_match in matches {
+        match best_matches.get(&search_match.game_id) {
+            Some(best) if best.relevance >= search_match.relevance => {}
+            _ => {
+                best_matches.insert(search_match.game_id, search_match);
+            }
+        }
+    }
+    let game_ids = best_matches.keys().collect::<Vec<_>>();
+    let popularity: HashMap<i32, f64> = conn
+        .prepare_cached("SELECT game_id, MAX(value) FROM popularity_primitives WHERE game_id IN (SELECT value FROM json_each(?1)) GROUP BY game_id")?
+        .query_map([serde_json::to_string(&game_ids)?], |row| {
+            Ok((row.get(0)?, row.get::<_, Option<f64>>(1)?.unwrap_or(0.0)))
+        })?
+        .collect::<Result<_, _>>()?;
+    let most_popular = popularity.values().fold(0.0, |a: f64, b| a.max(*b)).ln_1p();
+    let mut ranked = best_matches
+        .into_values()
+        .map(|search_match| {
+            let popularity = match most_popular > 0.0 {
+                true => {
+                    popularity
+                        .get(&search_match.game_id)
+                        .copied()
+                        .unwrap_or(0.0)
+                        .max(0.0)
+                        .ln_1p()
+                        / most_popular
+                }
+                false => 0.0,
+            };
+            let score =
+                (1.0 - POPULARITY_WEIGHT) * search_match.relevance + POPULARITY_WEIGHT * popularity;
+            (search_match, score)
+        })
+        .collect::<Vec<_>>();
+    ranked.sort_by(|(a, a_score), (b, b_score)| {
+        b_score.total_cmp(a_score).then(a.game_id.cmp(&b.game_id))
+    });
+    Ok(ranked)
 }
 
 #[tauri::command]
 pub fn search_game(
+    app_handle: tauri::AppHandle,
     state: State<'_, DatabaseConnections>,
     search_query: String,
-) -> Result<Vec<GameInfo>, Error> {
-    let results: Vec<i32>;
-    {
-        let conn = state.igdb_conn.lock().unwrap();
-        let mut stmt = conn.prepare("SELECT rowid FROM games_fts WHERE name MATCH ?;")?;
-        results = stmt
-            .query_map([search_query.replace("'", " ")], |row| row.get(0))?
-            .collect::<Result<_, _>>()?;
+    filter: GameFilter,
+    page: u32,
+    page_size: u32,
+) -> Result<GamePage<GameSearchResult>, Error> {
+    let terms = search_terms(&search_query);
+    if terms.is_empty() {
+        return Ok(GamePage {
+            games: vec![],
+            total: 0,
+        });
     }
-    let games = get_games_by_id(state, results)?;
-    Ok(games)
+    let content_filter = get_user_settings(app_handle)?.content_filter;
+    let (conditions, params) = game_filter_conditions(&state, &content_filter, &filter)?;
+    // Every match is ranked before the page is cut, so pages don't overlap or skip games.
+    let ranked = {
+        let conn = state.igdb_conn.lock().unwrap();
+        let mut matches = find_text_matches(&conn, &terms, &conditions, &params)?;
+        if matches.is_empty() {
+            matches = find_fuzzy_matches(&conn, &terms, &conditions, &params)?;
+        }
+        rank_matches(&conn, matches)?
+    };
+    let total = ranked.len();
+    let ranked = ranked
+        .into_iter()
+        .skip(usize::try_from(page_offset(page, page_size)).unwrap_or(usize::MAX))
+        .take(page_size as usize)
+        .collect::<Vec<_>>();
+    let mut games: HashMap<i32, GameInfo> = games_by_id(
+        &state,
+        &content_filter,
+        &ranked
+            .iter()
+            .map(|(search_match, _)| search_match.game_id)
+            .collect::<Vec<_>>(),
+    )?
+    .into_iter()
+    .map(|game| (game.id, game))
+    .collect();
+    let results = ranked
+        .into_iter()
+        .filter_map(|(search_match, score)| {
+            games
+                .remove(&search_match.game_id)
+                .map(|game| GameSearchResult {
+                    game,
+                    matched_name: search_match.name,
+                    matched_by: search_match.matched_by,
+                    score,
+                })
+        })
+        .collect();
+    Ok(GamePage {
+        games: results,
+        total,
+    })
 }
 
 pub fn get_games_from_links(
     state: State<'_, DatabaseConnections>,
+    content_filter: &ContentFilterSettings,
     links: Vec<String>,
 ) -> Result<Vec<GameInfo>, Error> {
+    let mut conditions = vec!["w.url IN (SELECT value FROM json_each(:links))"];
+    let mut params: NamedParams = vec![(":links", Box::new(serde_json::to_string(&links)?))];
+    push_content_conditions(content_filter, &mut conditions, &mut params)?;
     let conn = state.igdb_conn.lock().unwrap();
-    let formatted_links = links
-        .iter()
-        .map(|l| format!("'{}'", l))
-        .collect::<Vec<String>>();
-    let mut stmt = conn.prepare(
-        format!(
-            "SELECT {} WHERE w.url IN ({}) AND g.category IN (0, 4, 8, 9) AND p.name NOT IN ('Android', 'iOS') AND g.version_parent IS NULL GROUP BY g.id;",
-            game_info_columns(),
-            formatted_links.join(",").as_str()
-        )
-        .as_str(),
-    )?;
+    let mut stmt = conn.prepare(&format!(
+        "SELECT {}{} GROUP BY g.id",
+        game_info_columns(),
+        where_clause(&conditions)
+    ))?;
     let games = stmt
-        .query_map([], game_info_from_row)?
+        .query_map(named_params(&params).as_slice(), game_info_from_row)?
         .collect::<Result<Vec<_>, _>>()?;
     Ok(games)
 }
@@ -0,0 +1,181 @@
+use std::{fs, path::PathBuf};
+
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::State;
+
+use crate::{
+    database::{log_from_row, timestamp_from_row, to_storage_timestamp, Log},
+    DatabaseConnections, Error,
+};
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct JournalEntry {
+    pub id: i32,
+    pub created_at: String,
+    pub updated_at: String,
+    pub log_id: i32,
+    pub entry_date: String,
+    pub content: String,
+    pub is_spoiler: bool,
+    pub session_log_id: Option<i32>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct JournalEntryData {
+    pub log_id: i32,
+    pub entry_date: String,
+    pub content: String,
+    pub is_spoiler: bool,
+    pub session_log_id: Option<i32>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct JournalEntryUpdateData {
+    id: i32,
+    pub entry_date: String,
+    pub content: String,
+    pub is_spoiler: bool,
+    pub session_log_id: Option<i32>,
+}
+
+#[derive(Debug, serde::Serialize)]
+struct JournalExport {
+    log: Log,
+    journal_entries: Vec<JournalEntry>,
+}
+
+fn journal_entry_from_row(row: &rusqlite::Row) -> Result<JournalEntry, rusqlite::Error> {
+    Ok(JournalEntry {
+        id: row.get("id")?,
+        created_at: timestamp_from_row(row, "created_at", None)?,
+        updated_at: timestamp_from_row(row, "updated_at", None)?,
+        log_id: row.get("log_id")?,
+        entry_date: timestamp_from_row(row, "entry_date", Some("entry_date_offset"))?,
+        content: row.get("content")?,
+        is_spoiler: row.get("is_spoiler")?,
+        session_log_id: row.get("session_log_id")?,
+    })
+}
+
+/// Checks that a session link points at another log of the same game.
+fn validate_session_log(
+    conn: &Connection,
+    log_id: i32,
+    session_log_id: Option<i32>,
+) -> Result<(), Error> {
+    let session_log_id = match session_log_id {
+        Some(id) => id,
+        None => return Ok(()),
+    };
+    let same_game = conn
+        .query_row(
+            "SELECT s.game_id = l.game_id FROM logs s, logs l WHERE s.id = ?1 AND l.id = ?2",
+            [session_log_id, log_id],
+            |row| row.get::<usize, bool>(0),
+        )
+        .optional()?;
+    match same_game {
+        Some(true) => Ok(()),
+        Some(false) => Err(Error::from(format!(
+            "Session log {} is not for the same game as log {}",
+            session_log_id, log_id
+        ))),
+        None => Err(Error::from(format!(
+            "Log {} or session log {} not found",
+            log_id, session_log_id
+        ))),
+    }
+}
+
+fn get_log_journal_entries(conn: &Connection, log_id: i32) -> Result<Vec<JournalEntry>, Error> {
+    let mut stmt = conn.prepare(
+        "SELECT * FROM journal_entries WHERE log_id = ? ORDER BY entry_date ASC, id ASC",
+    )?;
+    let entries = stmt
+        .query_map([log_id], journal_entry_from_row)?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(entries)
+}
+
+#[tauri::command]
+pub fn get_journal_entries(
+    state: State<DatabaseConnections>,
+    log_id: i32,
+) -> Result<Vec<JournalEntry>, Error> {
+    let conn = state.logs_conn.lock().unwrap();
+    get_log_journal_entries(&conn, log_id)
+}
+
+#[tauri::command]
+pub fn add_journal_entry(
+    state: State<DatabaseConnections>,
+    entry_data: JournalEntryData,
+) -> Result<i32, Error> {
+    let conn = state.logs_conn.lock().unwrap();
+    validate_session_log(&conn, entry_data.log_id, entry_data.session_log_id)?;
+    let (entry_date, entry_date_offset) = to_storage_timestamp(&entry_data.entry_date)?;
+    conn.execute(
+        "INSERT INTO journal_entries (log_id, entry_date, entry_date_offset, content, is_spoiler, session_log_id) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            entry_data.log_id,
+            entry_date,
+            entry_date_offset,
+            entry_data.content,
+            entry_data.is_spoiler,
+            entry_data.session_log_id,
+        ],
+    )?;
+    let id = conn.last_insert_rowid() as i32;
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn update_journal_entry(
+    state: State<DatabaseConnections>,
+    entry_data: JournalEntryUpdateData,
+) -> Result<i32, Error> {
+    let conn = state.logs_conn.lock().unwrap();
+    let log_id: i32 = conn.query_row(
+        "SELECT log_id FROM journal_entries WHERE id = ?",
+        [entry_data.id],
+        |row| row.get(0),
+    )?;
+    validate_session_log(&conn, log_id, entry_data.session_log_id)?;
+    let (entry_date, entry_date_offset) = to_storage_timestamp(&entry_data.entry_date)?;
+    conn.execute(
+        "UPDATE journal_entries SET entry_date = ?1, entry_date_offset = ?2, content = ?3, is_spoiler = ?4, session_log_id = ?5, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now') WHERE id = ?6",
+        params![
+            entry_date,
+            entry_date_offset,
+            entry_data.content,
+            entry_data.is_spoiler,
+            entry_data.session_log_id,
+            entry_data.id,
+        ],
+    )?;
+    Ok(entry_data.id)
+}
+
+#[tauri::command]
+pub fn delete_journal_entry(state: State<DatabaseConnections>, id: i32) -> Result<i32, Error> {
+    let conn = state.logs_conn.lock().unwrap();
+    conn.execute("DELETE FROM journal_entries WHERE id = ?", [id])?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn export_journal(
+    state: State<DatabaseConnections>,
+    log_id: i32,
+    to_file: PathBuf,
+) -> Result<PathBuf, Error> {
+    let conn = state.logs_conn.lock().unwrap();
+    let log = conn.query_row("SELECT * FROM logs WHERE id = ?", [log_id], log_from_row)?;
+    let journal_entries = get_log_journal_entries(&conn, log_id)?;
+    let export = JournalExport {
+        log,
+        journal_entries,
+    };
+    fs::write(&to_file, serde_json::to_string_pretty(&export)?)?;
+    Ok(to_file)
+}
@@ -20,12 +20,22 @@ use tauri::{
     Manager,
 };
 
+mod attachments;
+mod backlog;
+mod covers;
 mod data_import;
 mod database;
+mod dump_compression;
+mod dump_updates;
+mod dump_validation;
 mod dumps;
 mod helpers;
 mod igdb;
+mod journal;
+mod picker;
 mod process_monitor;
+mod recommendations;
+mod reviews;
 
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
@@ -49,6 +59,10 @@ pub enum Error {
     TomlSer(#[from] toml::ser::Error),
     #[error(transparent)]
     Csv(#[from] csv::Error),
+    #[error(transparent)]
+    ChronoParse(#[from] chrono::ParseError),
+    #[error(transparent)]
+    Image(#[from] image::ImageError),
     #[error("Error: {0}")]
     Custom(String),
 }
@@ -72,6 +86,11 @@ pub struct UserSettings {
     process_monitoring: ProcessMonitoringSettings,
     autostart: bool,
     new: bool,
+    review_dimensions: Vec<String>,
+    dump_source: String,
+    dump_updates: DumpUpdateSettings,
+    cover_cdn: String,
+    content_filter: ContentFilterSettings,
 }
 
 #[derive(serde::Serialize, Debug, Deserialize)]
@@ -80,6 +99,22 @@ struct ProcessMonitoringSettings {
     directory_depth: usize,
 }
 
+#[derive(serde::Serialize, Debug, Deserialize)]
+struct DumpUpdateSettings {
+    automatic: bool,
+    interval_hours: u32,
+    pause_on_metered: bool,
+    row_error_policy: dumps::RowErrorPolicy,
+}
+
+/// Which IGDB games make up the catalogue. An empty list doesn't filter anything.
+#[derive(serde::Serialize, Debug, Deserialize)]
+struct ContentFilterSettings {
+    categories: Vec<i32>,
+    excluded_platforms: Vec<String>,
+    include_versions: bool,
+}
+
 impl serde::Serialize for Error {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
     where
@@ -105,6 +140,7 @@ fn main() {
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_dialog::init())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .register_asynchronous_uri_scheme_protocol(covers::COVER_URI_SCHEME, covers::handle_cover_request)
         .on_window_event(|window, event| match event {
             tauri::WindowEvent::CloseRequested { api, ..} => {
                 let app_handle = window.app_handle();
@@ -172,6 +208,26 @@ fn main() {
                         },
                         autostart: false,
                         new: true,
+                        review_dimensions: reviews::DEFAULT_REVIEW_DIMENSIONS
+                            .iter()
+                            .map(|d| d.to_string())
+                            .collect(),
+                        dump_source: dumps::DEFAULT_DUMP_SOURCE.to_string(),
+                        dump_updates: DumpUpdateSettings {
+                            automatic: false,
+                            interval_hours: 24,
+                            pause_on_metered: true,
+                            row_error_policy: dumps::RowErrorPolicy::Skip,
+                        },
+                        cover_cdn: covers::DEFAULT_COVER_CDN.to_string(),
+                        content_filter: ContentFilterSettings {
+                            categories: igdb::DEFAULT_CATEGORIES.to_vec(),
+                            excluded_platforms: igdb::DEFAULT_EXCLUDED_PLATFORMS
+                                .iter()
+                                .map(|p| p.to_string())
+                                .collect(),
+                            include_versions: false,
+                        },
                     };
                     match helpers::create_dir_if_not_exists(app.path().config_dir()?.join("game-chronicle").as_path()) {
                         Ok(_) => {}
@@ -196,11 +252,14 @@ fn main() {
                 autostart_manager.disable().unwrap();
             }
             let (logs_conn, igdb_conn) = database::initialize_database(app.handle().clone()).unwrap();
-            igdb_conn.execute("INSERT INTO games_fts (games_fts) VALUES ('rebuild')", rusqlite::params![])?;
             app.manage(DatabaseConnections {
                 logs_conn: std::sync::Mutex::new(logs_conn),
                 igdb_conn: std::sync::Mutex::new(igdb_conn),
             });
+            app.manage(dumps::DumpImportState::default());
+            dump_updates::schedule_dump_updates(app.handle().clone());
+            app.manage(covers::CoverCache::default());
+            covers::prefetch_journal_covers(app.handle().clone());
             let schema_changes = helpers::get_schema_changes(app.handle())?;
             if let Some(ref igdb_changes) = schema_changes.igdb {
                 for (table_name, changes) in igdb_changes {
@@ -263,6 +322,7 @@ fn main() {
             database::add_executable_details,
             igdb::get_popular_games,
             igdb::search_game,
+            igdb::get_platforms,
             data_import::get_steam_data,
             data_import::import_igdb_games,
             dumps::get_local_dump_versions,
@@ -270,6 +330,24 @@ fn main() {
             dumps::get_all_dump_info,
             dumps::import_dumps,
             dumps::download_dumps,
+            dumps::cancel_import,
+            dumps::rollback_dump_import,
+            dumps::can_rollback_dump_import,
+            attachments::add_attachment,
+            attachments::get_attachments,
+            attachments::remove_attachment,
+            attachments::export_attachments,
+            journal::get_journal_entries,
+            journal::add_journal_entry,
+            journal::update_journal_entry,
+            journal::delete_journal_entry,
+            journal::export_journal,
+            reviews::get_review,
+            reviews::save_review,
+            reviews::delete_review,
+            recommendations::get_recommendations,
+            picker::pick_next_game,
+            backlog::get_backlog_estimate,
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
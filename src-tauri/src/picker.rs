@@ -0,0 +1,387 @@
+use std::{
+    cmp::Reverse,
+    collections::{HashMap, HashSet},
+};
+
+use chrono::{DateTime, Utc};
+use rand::distributions::{Distribution, WeightedIndex};
+use rusqlite::{params_from_iter, Connection};
+use tauri::State;
+
+use crate::{
+    database::LATEST_LOGS,
+    helpers::get_user_settings,
+    igdb::{games_by_id, GameInfo},
+    DatabaseConnections, Error,
+};
+
+const PICKABLE_STATUSES: [&str; 3] = ["wishlist", "backlog", "playing"];
+const MINIMUM_FAVOURITE_RATING: i32 = 4;
+const RECENT_FAVOURITES: i32 = 10;
+const BASE_SCORE: f32 = 0.01;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct PickWeights {
+    pub time_in_backlog: f32,
+    pub total_rating: f32,
+    pub popularity: f32,
+    pub length: f32,
+    pub similarity: f32,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct PickOptions {
+    pub statuses: Vec<String>,
+    pub weights: PickWeights,
+    pub max_hours: Option<f32>,
+    pub platform_id: Option<i32>,
+    pub avoid_recent_picks: i32,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Pick {
+    pub game: GameInfo,
+    pub log_id: i32,
+    pub chance: f32,
+    pub reasons: Vec<String>,
+}
+
+struct Candidate {
+    log_id: i32,
+    added_at: DateTime<Utc>,
+}
+
+struct ScoredCandidate {
+    game_id: i32,
+    score: f32,
+    reasons: Vec<(f32, String)>,
+}
+
+fn id_list<'a>(ids: impl Iterator<Item = &'a i32>) -> String {
+    ids.map(|id| id.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+/// Estimates how long a game takes, preferring IGDB's time to beat and falling back to our own
+/// completed logs of games similar to it.
+fn estimate_hours(
+    game_id: i32,
+    time_to_beat: &HashMap<i32, i64>,
+    similar_games: &HashMap<i32, HashSet<i32>>,
+    completed_minutes: &HashMap<i32, i32>,
+) -> Option<f32> {
+    if let Some(seconds) = time_to_beat.get(&game_id) {
+        return Some(*seconds as f32 / 3600.0);
+    }
+    let minutes = similar_games
+        .get(&game_id)?
+        .iter()
+        .filter_map(|id| completed_minutes.get(id))
+        .collect::<Vec<_>>();
+    if minutes.is_empty() {
+        return None;
+    }
+    Some(minutes.iter().copied().sum::<i32>() as f32 / minutes.len() as f32 / 60.0)
+}
+
+/// Games whose latest log has one of `statuses`, keyed by game id.
+fn load_candidates(
+    conn: &Connection,
+    statuses: &[String],
+) -> Result<HashMap<i32, Candidate>, Error> {
+    let mut stmt = conn.prepare(&format!(
+        "SELECT id, game_id, created_at FROM ({}) WHERE status IN ({})",
+        LATEST_LOGS,
+        vec!["?"; statuses.len()].join(",")
+    ))?;
+    let mut rows = stmt.query(params_from_iter(statuses.iter()))?;
+    let mut candidates = HashMap::new();
+    while let Some(row) = rows.next()? {
+        let created_at: String = row.get(2)?;
+        candidates.insert(
+            row.get(1)?,
+            Candidate {
+                log_id: row.get(0)?,
+                added_at: DateTime::parse_from_rfc3339(&created_at)?.with_timezone(&Utc),
+            },
+        );
+    }
+    Ok(candidates)
+}
+
+#[tauri::command]
+pub fn pick_next_game(
+    app_handle: tauri::AppHandle,
+    state: State<'_, DatabaseConnections>,
+    options: PickOptions,
+) -> Result<Option<Pick>, Error> {
+    for status in &options.statuses {
+        if !PICKABLE_STATUSES.contains(&status.as_str()) {
+            return Err(Error::from(format!(
+                "'{}' is not a pickable status",
+                status
+            )));
+        }
+    }
+    if options.statuses.is_empty() {
+        return Ok(None);
+    }
+
+    let mut candidates: HashMap<i32, Candidate>;
+    let mut favourites: HashMap<i32, i32> = HashMap::new();
+    let mut completed_minutes: HashMap<i32, i32> = HashMap::new();
+    let mut recent_picks: HashMap<i32, usize> = HashMap::new();
+    {
+        let conn = state.logs_conn.lock().unwrap();
+        candidates = load_candidates(&conn, &options.statuses)?;
+        let mut recent_picks_stmt =
+            conn.prepare("SELECT game_id FROM picks ORDER BY id DESC LIMIT ?")?;
+        for game_id in
+            recent_picks_stmt.query_map([options.avoid_recent_picks.max(0)], |row| row.get(0))?
+        {
+            let position = recent_picks.len();
+            recent_picks.entry(game_id?).or_insert(position);
+        }
+        let mut favourites_stmt = conn.prepare(
+            "SELECT game_id, MAX(rating) FROM logs WHERE rating >= ?1 GROUP BY game_id ORDER BY MAX(end_date) DESC LIMIT ?2",
+        )?;
+        for favourite in favourites_stmt
+            .query_map([MINIMUM_FAVOURITE_RATING, RECENT_FAVOURITES], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?
+        {
+            let (game_id, rating) = favourite?;
+            favourites.insert(game_id, rating);
+        }
+        let mut completed_stmt = conn.prepare(
+            "SELECT game_id, MAX(minutes_played) FROM logs WHERE status = 'completed' AND minutes_played > 0 GROUP BY game_id",
+        )?;
+        for completed in completed_stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))? {
+            let (game_id, minutes) = completed?;
+            completed_minutes.insert(game_id, minutes);
+        }
+    }
+    if candidates.is_empty() {
+        return Ok(None);
+    }
+
+    let mut ratings: HashMap<i32, f32> = HashMap::new();
+    let mut popularity: HashMap<i32, f32> = HashMap::new();
+    let mut time_to_beat: HashMap<i32, i64> = HashMap::new();
+    let mut similar_games: HashMap<i32, HashSet<i32>> = HashMap::new();
+    let mut favourite_names: HashMap<i32, String> = HashMap::new();
+    {
+        let conn = state.igdb_conn.lock().unwrap();
+        let candidate_ids = id_list(candidates.keys());
+        let mut stats_stmt = conn.prepare(&format!(
+            "SELECT g.id, g.total_rating, (SELECT AVG(pp.value) FROM popularity_primitives pp WHERE pp.game_id = g.id), (SELECT COALESCE(ttb.normally, ttb.hastily, ttb.completely) FROM game_time_to_beats ttb WHERE ttb.game_id = g.id) FROM games g WHERE g.id IN ({})",
+            candidate_ids
+        ))?;
+        let mut rows = stats_stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let game_id: i32 = row.get(0)?;
+            if let Some(rating) = row.get::<usize, Option<f32>>(1)? {
+                ratings.insert(game_id, rating);
+            }
+            if let Some(value) = row.get::<usize, Option<f64>>(2)? {
+                popularity.insert(game_id, value as f32);
+            }
+            if let Some(seconds) = row.get::<usize, Option<i64>>(3)? {
+                time_to_beat.insert(game_id, seconds);
+            }
+        }
+        if let Some(platform_id) = options.platform_id {
+            let mut platform_stmt = conn.prepare(&format!(
+                "SELECT game_id FROM game_platforms WHERE platform_id = ? AND game_id IN ({})",
+                candidate_ids
+            ))?;
+            let on_platform = platform_stmt
+                .query_map([platform_id], |row| row.get(0))?
+                .collect::<Result<HashSet<i32>, _>>()?;
+            candidates.retain(|game_id, _| on_platform.contains(game_id));
+        }
+        let mut similar_stmt = conn.prepare(&format!(
+            "SELECT game_id, similar_game_id FROM similar_games WHERE game_id IN ({}) OR game_id IN ({})",
+            candidate_ids,
+            id_list(favourites.keys())
+        ))?;
+        let mut rows = similar_stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let game_id: i32 = row.get(0)?;
+            let similar_game_id: i32 = row.get(1)?;
+            similar_games
+                .entry(game_id)
+                .or_default()
+                .insert(similar_game_id);
+            similar_games
+                .entry(similar_game_id)
+                .or_default()
+                .insert(game_id);
+        }
+        let mut names_stmt = conn.prepare(&format!(
+            "SELECT id, name FROM games WHERE id IN ({})",
+            id_list(favourites.keys())
+        ))?;
+        for name in names_stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))? {
+            let (game_id, name) = name?;
+            favourite_names.insert(game_id, name);
+        }
+    }
+
+    let content_filter = get_user_settings(app_handle)?.content_filter;
+    let mut games: HashMap<i32, GameInfo> = games_by_id(
+        &state,
+        &content_filter,
+        &candidates.keys().copied().collect::<Vec<_>>(),
+    )?
+    .into_iter()
+    .map(|game| (game.id, game))
+    .collect();
+    let max_popularity = popularity.values().copied().fold(0.0_f32, f32::max);
+    let now = Utc::now();
+    let weights = &options.weights;
+    let mut scored: Vec<ScoredCandidate> = vec![];
+    for (game_id, candidate) in &candidates {
+        if !games.contains_key(game_id) {
+            continue;
+        }
+        let hours = estimate_hours(*game_id, &time_to_beat, &similar_games, &completed_minutes);
+        if let Some(max_hours) = options.max_hours {
+            match hours {
+                Some(hours) if hours <= max_hours => {}
+                _ => continue,
+            }
+        }
+        let mut reasons: Vec<(f32, String)> = vec![];
+        let days_waiting = (now - candidate.added_at).num_days().max(0);
+        reasons.push((
+            weights.time_in_backlog * (days_waiting as f32 / 365.0).min(1.0),
+            format!("Waiting in your journal for {} days", days_waiting),
+        ));
+        if let Some(rating) = ratings.get(game_id) {
+            reasons.push((
+                weights.total_rating * rating / 100.0,
+                format!("Rated {:.0}/100 on IGDB", rating),
+            ));
+        }
+        if let Some(value) = popularity.get(game_id) {
+            if max_popularity > 0.0 {
+                let relative_popularity = value / max_popularity;
+                reasons.push((
+                    weights.popularity * relative_popularity,
+                    format!(
+                        "{:.0}% as popular as your most popular option",
+                        relative_popularity * 100.0
+                    ),
+                ));
+            }
+        }
+        match hours {
+            Some(hours) => reasons.push((
+                weights.length / (1.0 + hours / 20.0),
+                format!("Estimated at around {:.0} hours", hours),
+            )),
+            None => reasons.push((weights.length * 0.5, "Length unknown".to_string())),
+        }
+        let similar_favourites = similar_games
+            .get(game_id)
+            .map(|similar| {
+                let mut names = similar
+                    .iter()
+                    .filter(|id| favourites.contains_key(id))
+                    .filter_map(|id| favourite_names.get(id).cloned())
+                    .collect::<Vec<_>>();
+                names.sort();
+                names
+            })
+            .unwrap_or_default();
+        if !similar_favourites.is_empty() {
+            reasons.push((
+                weights.similarity * (similar_favourites.len() as f32 / 3.0).min(1.0),
+                format!(
+                    "Similar to {}, which you rated highly",
+                    similar_favourites.join(", ")
+                ),
+            ));
+        }
+        let score = BASE_SCORE + reasons.iter().map(|(c, _)| c.max(0.0)).sum::<f32>();
+        scored.push(ScoredCandidate {
+            game_id: *game_id,
+            score,
+            reasons,
+        });
+    }
+    let (mut scored, mut recently_picked): (Vec<_>, Vec<_>) = scored
+        .into_iter()
+        .partition(|candidate| !recent_picks.contains_key(&candidate.game_id));
+    if scored.is_empty() {
+        // Everything left was picked recently, so draw from the half picked longest ago.
+        recently_picked.sort_by_key(|candidate| Reverse(recent_picks[&candidate.game_id]));
+        recently_picked.truncate(recently_picked.len().div_ceil(2));
+        scored = recently_picked;
+    }
+    if scored.is_empty() {
+        return Ok(None);
+    }
+
+    scored.sort_by_key(|candidate| candidate.game_id);
+    let total_score = scored.iter().map(|candidate| candidate.score).sum::<f32>();
+    let distribution = WeightedIndex::new(scored.iter().map(|candidate| candidate.score))
+        .map_err(|e| Error::from(e.to_string()))?;
+    let ScoredCandidate {
+        game_id,
+        score,
+        mut reasons,
+    } = scored.swap_remove(distribution.sample(&mut rand::thread_rng()));
+    reasons.sort_by(|a, b| b.0.total_cmp(&a.0));
+    let game = games
+        .remove(&game_id)
+        .expect("Candidates are filtered to known games");
+    let log_id = candidates[&game_id].log_id;
+    {
+        let conn = state.logs_conn.lock().unwrap();
+        conn.execute("INSERT INTO picks (game_id) VALUES (?)", [game_id])?;
+    }
+    Ok(Some(Pick {
+        game,
+        log_id,
+        chance: score / total_score,
+        reasons: reasons
+            .into_iter()
+            .filter(|(contribution, _)| *contribution > 0.0)
+            .map(|(_, reason)| reason)
+            .collect(),
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_logs_database() -> Connection {
+        let conn = Connection::open_in_memory().unwrap();
+        conn.execute_batch(include_str!("../sql/initialize_database.sql"))
+            .unwrap();
+        conn
+    }
+
+    #[test]
+    fn candidates_follow_each_games_latest_log() {
+        let conn = create_logs_database();
+        conn.execute_batch(
+            "INSERT INTO logs (id, game_id, created_at, status) VALUES
+                (1, 10, '2024-01-01T00:00:00Z', 'backlog'),
+                (2, 10, '2024-03-01T00:00:00Z', 'completed'),
+                (3, 20, '2024-01-01T00:00:00Z', 'completed'),
+                (4, 20, '2024-02-01T00:00:00Z', 'backlog'),
+                (5, 30, '2024-01-01T00:00:00Z', 'wishlist');",
+        )
+        .unwrap();
+
+        let candidates = load_candidates(&conn, &["backlog".to_string()]).unwrap();
+
+        assert_eq!(candidates.keys().collect::<Vec<_>>(), vec![&20]);
+        assert_eq!(candidates[&20].log_id, 4);
+    }
+}
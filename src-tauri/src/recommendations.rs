@@ -0,0 +1,160 @@
+use std::collections::{HashMap, HashSet};
+
+use tauri::State;
+
+use crate::{
+    helpers::get_user_settings,
+    igdb::{games_by_id, GameInfo},
+    DatabaseConnections, Error,
+};
+
+const MINIMUM_FAVOURITE_RATING: i32 = 4;
+const ABANDONED_PENALTY: f32 = 1.5;
+const TOTAL_RATING_WEIGHT: f32 = 0.5;
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Recommendation {
+    pub game: GameInfo,
+    pub score: f32,
+    pub reasons: Vec<String>,
+}
+
+struct CandidateScore {
+    score: f32,
+    reasons: Vec<(f32, String)>,
+}
+
+fn id_list(ids: impl Iterator<Item = i32>) -> String {
+    ids.map(|id| id.to_string())
+        .collect::<Vec<String>>()
+        .join(",")
+}
+
+#[tauri::command]
+pub fn get_recommendations(
+    app_handle: tauri::AppHandle,
+    state: State<'_, DatabaseConnections>,
+    amount: usize,
+    include_logged: bool,
+) -> Result<Vec<Recommendation>, Error> {
+    let mut favourite_ratings: HashMap<i32, i32> = HashMap::new();
+    let mut abandoned_games: HashSet<i32> = HashSet::new();
+    let mut logged_games: HashSet<i32> = HashSet::new();
+    {
+        let conn = state.logs_conn.lock().unwrap();
+        let mut stmt = conn.prepare(
+            "SELECT game_id, MAX(rating), SUM(status = 'abandoned') FROM logs WHERE status != 'wishlist' GROUP BY game_id",
+        )?;
+        let mut rows = stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let game_id: i32 = row.get(0)?;
+            let rating: i32 = row.get::<usize, Option<i32>>(1)?.unwrap_or(0);
+            let times_abandoned: i32 = row.get(2)?;
+            logged_games.insert(game_id);
+            if rating >= MINIMUM_FAVOURITE_RATING {
+                favourite_ratings.insert(game_id, rating);
+            } else if times_abandoned > 0 {
+                abandoned_games.insert(game_id);
+            }
+        }
+        let mut wishlist_stmt =
+            conn.prepare("SELECT DISTINCT game_id FROM logs WHERE status = 'wishlist'")?;
+        for game_id in wishlist_stmt.query_map([], |row| row.get::<usize, i32>(0))? {
+            logged_games.insert(game_id?);
+        }
+    }
+    if favourite_ratings.is_empty() {
+        return Ok(vec![]);
+    }
+
+    let mut candidates: HashMap<i32, CandidateScore> = HashMap::new();
+    {
+        let conn = state.igdb_conn.lock().unwrap();
+        let source_ids = favourite_ratings
+            .keys()
+            .chain(abandoned_games.iter())
+            .copied();
+        let mut names: HashMap<i32, String> = HashMap::new();
+        let mut names_stmt = conn.prepare(&format!(
+            "SELECT id, name FROM games WHERE id IN ({})",
+            id_list(source_ids.clone())
+        ))?;
+        for name in names_stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))? {
+            let (id, name) = name?;
+            names.insert(id, name);
+        }
+        let mut similar_stmt = conn.prepare(&format!(
+            "SELECT sg.game_id, sg.similar_game_id, g.total_rating FROM similar_games sg JOIN games g ON g.id = sg.similar_game_id WHERE sg.game_id IN ({})",
+            id_list(source_ids)
+        ))?;
+        let mut rows = similar_stmt.query([])?;
+        while let Some(row) = rows.next()? {
+            let source_id: i32 = row.get(0)?;
+            let candidate_id: i32 = row.get(1)?;
+            let total_rating: Option<f32> = row.get(2)?;
+            if !include_logged && logged_games.contains(&candidate_id) {
+                continue;
+            }
+            let source_name = names
+                .get(&source_id)
+                .cloned()
+                .unwrap_or_else(|| format!("game {}", source_id));
+            let candidate = candidates
+                .entry(candidate_id)
+                .or_insert_with(|| CandidateScore {
+                    score: total_rating.unwrap_or(0.0) / 100.0 * TOTAL_RATING_WEIGHT,
+                    reasons: vec![],
+                });
+            match favourite_ratings.get(&source_id) {
+                Some(rating) => {
+                    let weight = (rating - MINIMUM_FAVOURITE_RATING + 1) as f32;
+                    candidate.score += weight;
+                    candidate.reasons.push((
+                        weight,
+                        format!("Because you rated {} {} stars", source_name, rating),
+                    ));
+                }
+                None => {
+                    candidate.score -= ABANDONED_PENALTY;
+                    candidate.reasons.push((
+                        -ABANDONED_PENALTY,
+                        format!("Similar to {}, which you abandoned", source_name),
+                    ));
+                }
+            }
+        }
+    }
+
+    let mut ranked = candidates
+        .into_iter()
+        .filter(|(_, candidate)| candidate.score > 0.0)
+        .collect::<Vec<_>>();
+    ranked.sort_by(|a, b| b.1.score.total_cmp(&a.1.score).then(a.0.cmp(&b.0)));
+    let ranked_ids = ranked.iter().map(|(id, _)| *id).collect::<Vec<i32>>();
+    let content_filter = get_user_settings(app_handle)?.content_filter;
+    let mut games: HashMap<i32, GameInfo> = games_by_id(&state, &content_filter, &ranked_ids)?
+        .into_iter()
+        .map(|game| (game.id, game))
+        .collect();
+    let mut recommendations = vec![];
+    for (id, mut candidate) in ranked {
+        if recommendations.len() >= amount {
+            break;
+        }
+        let game = match games.remove(&id) {
+            Some(game) => game,
+            None => continue,
+        };
+        candidate.reasons.sort_by(|a, b| b.0.total_cmp(&a.0));
+        recommendations.push(Recommendation {
+            game,
+            score: candidate.score,
+            reasons: candidate
+                .reasons
+                .into_iter()
+                .map(|(_, reason)| reason)
+                .collect(),
+        });
+    }
+    Ok(recommendations)
+}
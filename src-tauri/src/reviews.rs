@@ -0,0 +1,143 @@
+use rusqlite::{params, Connection, OptionalExtension};
+use tauri::State;
+
+use crate::{database::timestamp_from_row, helpers::get_user_settings, DatabaseConnections, Error};
+
+pub const DEFAULT_REVIEW_DIMENSIONS: [&str; 5] =
+    ["story", "gameplay", "visuals", "audio", "performance"];
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ReviewScore {
+    pub dimension: String,
+    pub score: i32,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct Review {
+    pub id: i32,
+    pub created_at: String,
+    pub updated_at: String,
+    pub log_id: i32,
+    pub verdict: Option<String>,
+    pub would_replay: bool,
+    pub scores: Vec<ReviewScore>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ReviewData {
+    pub log_id: i32,
+    pub verdict: Option<String>,
+    pub would_replay: bool,
+    pub scores: Vec<ReviewScore>,
+}
+
+#[derive(Debug, serde::Serialize, serde::Deserialize)]
+pub struct ReviewScoreAverage {
+    pub dimension: String,
+    pub average_score: f32,
+    pub total_reviews: i32,
+}
+
+/// Averages every scored dimension over reviews of logs that ended within the given range.
+pub fn get_review_score_averages(
+    conn: &Connection,
+    start_date: &str,
+    end_date: &str,
+) -> Result<Vec<ReviewScoreAverage>, Error> {
+    let mut stmt = conn.prepare("SELECT rs.dimension, AVG(rs.score), COUNT(*) FROM review_scores rs JOIN reviews r ON r.id = rs.review_id JOIN logs l ON l.id = r.log_id WHERE (l.end_date BETWEEN ?1 AND ?2) GROUP BY rs.dimension ORDER BY rs.dimension")?;
+    let averages = stmt
+        .query_map([start_date, end_date], |row| {
+            Ok(ReviewScoreAverage {
+                dimension: row.get(0)?,
+                average_score: row.get::<usize, f64>(1)? as f32,
+                total_reviews: row.get(2)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(averages)
+}
+
+#[tauri::command]
+pub fn get_review(state: State<DatabaseConnections>, log_id: i32) -> Result<Option<Review>, Error> {
+    let conn = state.logs_conn.lock().unwrap();
+    let review = conn
+        .query_row("SELECT * FROM reviews WHERE log_id = ?", [log_id], |row| {
+            Ok(Review {
+                id: row.get("id")?,
+                created_at: timestamp_from_row(row, "created_at", None)?,
+                updated_at: timestamp_from_row(row, "updated_at", None)?,
+                log_id: row.get("log_id")?,
+                verdict: row.get("verdict")?,
+                would_replay: row.get("would_replay")?,
+                scores: vec![],
+            })
+        })
+        .optional()?;
+    let mut review = match review {
+        Some(review) => review,
+        None => return Ok(None),
+    };
+    let mut stmt =
+        conn.prepare("SELECT dimension, score FROM review_scores WHERE review_id = ?")?;
+    review.scores = stmt
+        .query_map([review.id], |row| {
+            Ok(ReviewScore {
+                dimension: row.get(0)?,
+                score: row.get(1)?,
+            })
+        })?
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Some(review))
+}
+
+#[tauri::command]
+pub fn save_review(
+    app_handle: tauri::AppHandle,
+    state: State<DatabaseConnections>,
+    review_data: ReviewData,
+) -> Result<i32, Error> {
+    let review_dimensions = get_user_settings(app_handle)?.review_dimensions;
+    for score in &review_data.scores {
+        if !review_dimensions.contains(&score.dimension) {
+            return Err(Error::from(format!(
+                "'{}' is not a configured review dimension",
+                score.dimension
+            )));
+        }
+    }
+    let mut conn = state.logs_conn.lock().unwrap();
+    let transaction = conn.transaction()?;
+    transaction.execute(
+        "INSERT INTO reviews (log_id, verdict, would_replay) VALUES (?1, ?2, ?3) ON CONFLICT (log_id) DO UPDATE SET verdict = excluded.verdict, would_replay = excluded.would_replay, updated_at = strftime('%Y-%m-%dT%H:%M:%SZ', 'now')",
+        params![review_data.log_id, review_data.verdict, review_data.would_replay],
+    )?;
+    let id: i32 = transaction.query_row(
+        "SELECT id FROM reviews WHERE log_id = ?",
+        [review_data.log_id],
+        |row| row.get(0),
+    )?;
+    transaction.execute("DELETE FROM review_scores WHERE review_id = ?", [id])?;
+    {
+        let mut insert_score_stmt = transaction.prepare(
+            "INSERT INTO review_scores (review_id, dimension, score) VALUES (?1, ?2, ?3)",
+        )?;
+        for score in &review_data.scores {
+            insert_score_stmt.execute(params![id, score.dimension, score.score])?;
+        }
+    }
+    transaction.commit()?;
+    Ok(id)
+}
+
+#[tauri::command]
+pub fn delete_review(state: State<DatabaseConnections>, log_id: i32) -> Result<i32, Error> {
+    let mut conn = state.logs_conn.lock().unwrap();
+    let transaction = conn.transaction()?;
+    transaction.execute(
+        "DELETE FROM review_scores WHERE review_id IN (SELECT id FROM reviews WHERE log_id = ?)",
+        [log_id],
+    )?;
+    transaction.execute("DELETE FROM reviews WHERE log_id = ?", [log_id])?;
+    transaction.commit()?;
+    Ok(log_id)
+}
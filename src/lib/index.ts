@@ -7,8 +7,8 @@ export function logDataFromForm(igdbGame: GameInfo, formData: z.infer<LogFormSch
 	return {
 		status: formData.status.toLowerCase() as StatusOption,
 		rating: formData.rating,
-		start_date: formData.logStartDate.toISOString(),
-		end_date: formData.logEndDate.toISOString(),
+		start_date: toRfc3339(formData.logStartDate),
+		end_date: toRfc3339(formData.logEndDate),
 		notes: formData.notes ?? '',
 		minutes_played: formData.timePlayedHours * 60 + formData.timePlayedMinutes,
 		game_id: igdbGame.id
@@ -18,3 +18,14 @@ export function logDataFromForm(igdbGame: GameInfo, formData: z.infer<LogFormSch
 export function toTitleCase(str: string) {
 	return str.replace(/\b\w/g, (char) => char.toUpperCase());
 }
+
+export function toRfc3339(date: Date) {
+	const pad = (value: number) => String(Math.floor(Math.abs(value))).padStart(2, '0');
+	const offsetMinutes = -date.getTimezoneOffset();
+	const sign = offsetMinutes >= 0 ? '+' : '-';
+	return (
+		`${date.getFullYear()}-${pad(date.getMonth() + 1)}-${pad(date.getDate())}` +
+		`T${pad(date.getHours())}:${pad(date.getMinutes())}:${pad(date.getSeconds())}` +
+		`${sign}${pad(offsetMinutes / 60)}:${pad(offsetMinutes % 60)}`
+	);
+}
@@ -0,0 +1,34 @@
+import { invoke } from '@tauri-apps/api/core';
+import { z } from 'zod';
+
+const attachmentSchema = z.object({
+	id: z.number(),
+	log_id: z.number(),
+	created_at: z.string(),
+	file_name: z.string(),
+	size_bytes: z.number(),
+	path: z.string(),
+	thumbnail_path: z.string().nullable()
+});
+
+export type Attachment = z.infer<typeof attachmentSchema>;
+
+export async function addAttachment(logId: number, filePath: string) {
+	const attachment = await invoke('add_attachment', { logId, filePath });
+	return attachmentSchema.parse(attachment);
+}
+
+export async function getAttachments(logId: number) {
+	const attachments: object[] = await invoke('get_attachments', { logId });
+	return attachments.map((attachment: unknown) => attachmentSchema.parse(attachment));
+}
+
+export async function removeAttachment(id: number) {
+	const removedAttachmentId = await invoke('remove_attachment', { id });
+	return removedAttachmentId as number;
+}
+
+export async function exportAttachments(logId: number, toDirectory: string) {
+	const exportedPaths = await invoke('export_attachments', { logId, toDirectory });
+	return exportedPaths as string[];
+}
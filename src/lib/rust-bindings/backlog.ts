@@ -0,0 +1,17 @@
+import { invoke } from '@tauri-apps/api/core';
+import { z } from 'zod';
+
+const backlogEstimateSchema = z.object({
+	total_games: z.number(),
+	estimated_games: z.number(),
+	total_hours: z.number(),
+	weekly_hours: z.number(),
+	weeks_to_finish: z.number().nullable()
+});
+
+export type BacklogEstimate = z.infer<typeof backlogEstimateSchema>;
+
+export async function getBacklogEstimate() {
+	const estimate = await invoke('get_backlog_estimate');
+	return backlogEstimateSchema.parse(estimate);
+}
@@ -0,0 +1,6 @@
+import { convertFileSrc } from '@tauri-apps/api/core';
+
+/** URL of a cover served from the local cover cache, which downloads it on first use. */
+export function coverUrl(imageId: string) {
+	return convertFileSrc(imageId, 'cover');
+}
@@ -5,7 +5,14 @@ import { z } from 'zod';
 const dashboardStatisticsSchema = z.object({
 	total_minutes_played: z.number(),
 	total_games_played: z.number(),
-	total_games_completed: z.number()
+	total_games_completed: z.number(),
+	review_score_averages: z.array(
+		z.object({
+			dimension: z.string(),
+			average_score: z.number(),
+			total_reviews: z.number()
+		})
+	)
 });
 
 const logSchema = z.object({
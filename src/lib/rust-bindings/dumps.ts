@@ -6,17 +6,113 @@ const dumpVersionsSchema = z.object({
 	covers: z.string().optional(),
 	websites: z.string().optional(),
 	platforms: z.string().optional(),
-	popularity_primitives: z.string().optional()
+	popularity_primitives: z.string().optional(),
+	game_time_to_beats: z.string().optional(),
+	genres: z.string().optional(),
+	themes: z.string().optional(),
+	game_modes: z.string().optional(),
+	franchises: z.string().optional(),
+	collections: z.string().optional(),
+	companies: z.string().optional(),
+	involved_companies: z.string().optional(),
+	release_dates: z.string().optional(),
+	alternative_names: z.string().optional(),
+	game_localizations: z.string().optional()
 });
 export type DumpVersions = z.infer<typeof dumpVersionsSchema>;
 
 const dumpInfoSchema = z.object({
-	name: z.enum(['games', 'covers', 'websites', 'platforms', 'popularity_primitives']),
+	name: z.enum([
+		'games',
+		'covers',
+		'websites',
+		'platforms',
+		'popularity_primitives',
+		'game_time_to_beats',
+		'genres',
+		'themes',
+		'game_modes',
+		'franchises',
+		'collections',
+		'companies',
+		'involved_companies',
+		'release_dates',
+		'alternative_names',
+		'game_localizations'
+	]),
 	url: z.string(),
-	version: z.string()
+	version: z.string(),
+	checksum: z.string().nullable(),
+	delta_since: z.string().nullable(),
+	deleted_url: z.string().nullable(),
+	deleted_checksum: z.string().nullable(),
+	compression: z.enum(['gzip', 'zstd']).nullable()
 });
 type DumpInfo = z.infer<typeof dumpInfoSchema>;
 
+export const dumpProgressSchema = z.object({
+	name: z.string(),
+	stage: z.enum(['download', 'import']),
+	bytes_processed: z.number(),
+	total_bytes: z.number().nullable(),
+	rows_processed: z.number().nullable()
+});
+export type DumpProgress = z.infer<typeof dumpProgressSchema>;
+
+export const rowErrorPolicySchema = z.enum(['skip', 'abort']);
+export type RowErrorPolicy = z.infer<typeof rowErrorPolicySchema>;
+
+export const dumpImportSummarySchema = z.object({
+	skipped_rows: z.number(),
+	row_errors: z.array(
+		z.object({
+			table: z.string(),
+			row: z.number(),
+			message: z.string()
+		})
+	),
+	removed_games: z.array(
+		z.object({
+			game_id: z.number(),
+			name: z.string().nullable(),
+			merged_into: z.number().nullable()
+		})
+	),
+	remapped_games: z.array(
+		z.object({
+			from_game_id: z.number(),
+			to_game_id: z.number(),
+			logs: z.number(),
+			executables: z.number()
+		})
+	),
+	unresolved_game_ids: z.array(z.number()),
+	warnings: z.array(z.string())
+});
+export type DumpImportSummary = z.infer<typeof dumpImportSummarySchema>;
+
+export const dumpImportFailureSchema = z.object({
+	table: z.string().nullable(),
+	row: z.number().nullable(),
+	message: z.string(),
+	cancelled: z.boolean(),
+	validation: z.array(
+		z.object({
+			table: z.string(),
+			missing_columns: z.array(z.string()),
+			extra_columns: z.array(z.string()),
+			bad_row_count: z.number(),
+			bad_rows: z.array(
+				z.object({
+					row: z.number(),
+					message: z.string()
+				})
+			)
+		})
+	)
+});
+export type DumpImportFailure = z.infer<typeof dumpImportFailureSchema>;
+
 export async function getLocalDumpVersions() {
 	const dumpVersions = await invoke('get_local_dump_versions');
 	return dumpVersionsSchema.parse(dumpVersions);
@@ -35,6 +131,19 @@ export async function downloadDumps(dumpInfo: DumpInfo[], toDirectory: string) {
 	await invoke('download_dumps', { dumpInfo, toDirectory });
 }
 
-export async function importDumps(fromDirectory: string) {
-	await invoke('import_dumps', { fromDirectory });
+export async function importDumps(fromDirectory: string, rowErrorPolicy: RowErrorPolicy) {
+	await invoke('import_dumps', { fromDirectory, rowErrorPolicy });
+}
+
+export async function cancelImport() {
+	await invoke('cancel_import');
+}
+
+export async function rollbackDumpImport() {
+	await invoke('rollback_dump_import');
+}
+
+export async function canRollbackDumpImport() {
+	const canRollback = await invoke('can_rollback_dump_import');
+	return z.boolean().parse(canRollback);
 }
@@ -9,7 +9,21 @@ const userSettingsSchema = z.object({
 		directory_depth: z.number()
 	}),
 	autostart: z.boolean(),
-	new: z.boolean()
+	new: z.boolean(),
+	review_dimensions: z.array(z.string()),
+	dump_source: z.string(),
+	dump_updates: z.object({
+		automatic: z.boolean(),
+		interval_hours: z.number(),
+		pause_on_metered: z.boolean(),
+		row_error_policy: z.enum(['skip', 'abort'])
+	}),
+	cover_cdn: z.string(),
+	content_filter: z.object({
+		categories: z.array(z.number()),
+		excluded_platforms: z.array(z.string()),
+		include_versions: z.boolean()
+	})
 });
 
 export type UserSettings = z.infer<typeof userSettingsSchema>;
@@ -9,22 +9,115 @@ export const gameInfoSchema = z.object({
 	similar_games: z.array(z.number()).optional().nullable(),
 	category: z.number(),
 	version_parent: z.number().optional().nullable(),
-	total_rating: z.number().optional().nullable()
+	total_rating: z.number().optional().nullable(),
+	time_to_beat: z
+		.object({
+			hastily: z.number().nullable(),
+			normally: z.number().nullable(),
+			completely: z.number().nullable()
+		})
+		.optional()
+		.nullable(),
+	summary: z.string().nullable(),
+	storyline: z.string().nullable(),
+	genres: z.array(z.string()),
+	themes: z.array(z.string()),
+	game_modes: z.array(z.string()),
+	franchises: z.array(z.string()),
+	collections: z.array(z.string()),
+	developers: z.array(z.string()),
+	publishers: z.array(z.string()),
+	release_dates: z.array(
+		z.object({
+			date: z.number().nullable(),
+			platform: z.string().nullable(),
+			region: z.number().nullable()
+		})
+	)
 });
 
 export type GameInfo = z.infer<typeof gameInfoSchema>;
 
+export const gameSearchResultSchema = gameInfoSchema.extend({
+	matched_name: z.string(),
+	matched_by: z.enum(['name', 'alternative_name', 'localization']),
+	score: z.number()
+});
+
+export type GameSearchResult = z.infer<typeof gameSearchResultSchema>;
+
 export async function getGamesById(gameIds: number[]) {
 	const games: object[] = await invoke('get_games_by_id', { gameIds });
 	return games.map((game: unknown) => gameInfoSchema.parse(game));
 }
 
-export async function getPopularGames(amount: number) {
-	const games: object[] = await invoke('get_popular_games', { amount });
-	return games.map((game: unknown) => gameInfoSchema.parse(game));
+export const gameCategories = [
+	{ value: 0, label: 'Main game' },
+	{ value: 1, label: 'DLC' },
+	{ value: 2, label: 'Expansion' },
+	{ value: 3, label: 'Bundle' },
+	{ value: 4, label: 'Standalone expansion' },
+	{ value: 5, label: 'Mod' },
+	{ value: 6, label: 'Episode' },
+	{ value: 7, label: 'Season' },
+	{ value: 8, label: 'Remake' },
+	{ value: 9, label: 'Remaster' },
+	{ value: 10, label: 'Expanded game' },
+	{ value: 11, label: 'Port' },
+	{ value: 12, label: 'Fork' },
+	{ value: 13, label: 'Pack' },
+	{ value: 14, label: 'Update' }
+];
+
+export type GameFilter = {
+	platform_ids: number[];
+	categories: number[];
+	release_year_from: number | null;
+	release_year_to: number | null;
+	min_total_rating: number | null;
+	has_cover: boolean;
+	not_in_journal: boolean;
+};
+
+export const emptyGameFilter: GameFilter = {
+	platform_ids: [],
+	categories: [],
+	release_year_from: null,
+	release_year_to: null,
+	min_total_rating: null,
+	has_cover: false,
+	not_in_journal: false
+};
+
+function gamePageSchema<T extends z.ZodTypeAny>(gameSchema: T) {
+	return z.object({
+		games: z.array(gameSchema),
+		total: z.number()
+	});
 }
 
-export async function searchGame(query: string) {
-	const games: object[] = await invoke('search_game', { searchQuery: query });
-	return games.map((game: unknown) => gameInfoSchema.parse(game));
+export async function getPopularGames(filter: GameFilter, page: number, pageSize: number) {
+	const gamePage = await invoke('get_popular_games', { filter, page, pageSize });
+	return gamePageSchema(gameInfoSchema).parse(gamePage);
+}
+
+export async function searchGame(
+	query: string,
+	filter: GameFilter,
+	page: number,
+	pageSize: number
+) {
+	const gamePage = await invoke('search_game', { searchQuery: query, filter, page, pageSize });
+	return gamePageSchema(gameSearchResultSchema).parse(gamePage);
+}
+
+const platformSchema = z.object({
+	id: z.number(),
+	name: z.string()
+});
+export type Platform = z.infer<typeof platformSchema>;
+
+export async function getPlatforms() {
+	const platforms = await invoke('get_platforms');
+	return z.array(platformSchema).parse(platforms);
 }
@@ -0,0 +1,53 @@
+import { invoke } from '@tauri-apps/api/core';
+import { z } from 'zod';
+
+const journalEntrySchema = z.object({
+	id: z.number(),
+	created_at: z.string(),
+	updated_at: z.string(),
+	log_id: z.number(),
+	entry_date: z.string(),
+	content: z.string(),
+	is_spoiler: z.boolean(),
+	session_log_id: z.number().nullable()
+});
+
+export const journalEntryDataSchema = journalEntrySchema.omit({
+	id: true,
+	created_at: true,
+	updated_at: true
+});
+
+const journalEntryUpdateSchema = journalEntrySchema.omit({
+	created_at: true,
+	updated_at: true,
+	log_id: true
+});
+
+export type JournalEntry = z.infer<typeof journalEntrySchema>;
+export type JournalEntryData = z.infer<typeof journalEntryDataSchema>;
+
+export async function getJournalEntries(logId: number) {
+	const entries: object[] = await invoke('get_journal_entries', { logId });
+	return entries.map((entry: unknown) => journalEntrySchema.parse(entry));
+}
+
+export async function addJournalEntry(entry: JournalEntryData) {
+	const addedEntryId = await invoke('add_journal_entry', { entryData: entry });
+	return addedEntryId as number;
+}
+
+export async function updateJournalEntry(entry: z.infer<typeof journalEntryUpdateSchema>) {
+	const updatedEntryId = await invoke('update_journal_entry', { entryData: entry });
+	return updatedEntryId as number;
+}
+
+export async function deleteJournalEntry(id: number) {
+	const deletedEntryId = await invoke('delete_journal_entry', { id });
+	return deletedEntryId as number;
+}
+
+export async function exportJournal(logId: number, toFile: string) {
+	const exportedPath = await invoke('export_journal', { logId, toFile });
+	return exportedPath as string;
+}
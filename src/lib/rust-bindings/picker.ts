@@ -0,0 +1,31 @@
+import { invoke } from '@tauri-apps/api/core';
+import { z } from 'zod';
+import { gameInfoSchema } from './igdb';
+
+const pickSchema = z.object({
+	game: gameInfoSchema,
+	log_id: z.number(),
+	chance: z.number(),
+	reasons: z.array(z.string())
+});
+
+export type Pick = z.infer<typeof pickSchema>;
+
+export type PickOptions = {
+	statuses: ('wishlist' | 'backlog' | 'playing')[];
+	weights: {
+		time_in_backlog: number;
+		total_rating: number;
+		popularity: number;
+		length: number;
+		similarity: number;
+	};
+	max_hours: number | null;
+	platform_id: number | null;
+	avoid_recent_picks: number;
+};
+
+export async function pickNextGame(options: PickOptions) {
+	const pick = await invoke('pick_next_game', { options });
+	return pickSchema.nullable().parse(pick);
+}
@@ -0,0 +1,21 @@
+import { invoke } from '@tauri-apps/api/core';
+import { z } from 'zod';
+import { gameInfoSchema } from './igdb';
+
+const recommendationSchema = z.object({
+	game: gameInfoSchema,
+	score: z.number(),
+	reasons: z.array(z.string())
+});
+
+export type Recommendation = z.infer<typeof recommendationSchema>;
+
+export async function getRecommendations(amount: number, includeLogged = false) {
+	const recommendations: object[] = await invoke('get_recommendations', {
+		amount,
+		includeLogged
+	});
+	return recommendations.map((recommendation: unknown) =>
+		recommendationSchema.parse(recommendation)
+	);
+}
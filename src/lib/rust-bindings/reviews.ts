@@ -0,0 +1,37 @@
+import { invoke } from '@tauri-apps/api/core';
+import { z } from 'zod';
+
+const reviewScoreSchema = z.object({
+	dimension: z.string(),
+	score: z.number()
+});
+
+const reviewSchema = z.object({
+	id: z.number(),
+	created_at: z.string(),
+	updated_at: z.string(),
+	log_id: z.number(),
+	verdict: z.string().nullable(),
+	would_replay: z.boolean(),
+	scores: z.array(reviewScoreSchema)
+});
+
+export const reviewDataSchema = reviewSchema.omit({ id: true, created_at: true, updated_at: true });
+
+export type Review = z.infer<typeof reviewSchema>;
+export type ReviewData = z.infer<typeof reviewDataSchema>;
+
+export async function getReview(logId: number) {
+	const review = await invoke('get_review', { logId });
+	return reviewSchema.nullable().parse(review);
+}
+
+export async function saveReview(review: ReviewData) {
+	const savedReviewId = await invoke('save_review', { reviewData: review });
+	return savedReviewId as number;
+}
+
+export async function deleteReview(logId: number) {
+	const deletedLogId = await invoke('delete_review', { logId });
+	return deletedLogId as number;
+}
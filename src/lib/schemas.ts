@@ -63,7 +63,21 @@ export const settingsSchema = z.object({
 	executablePaths: z.array(z.string()),
 	processMonitoringEnabled: z.boolean(),
 	processMonitoringDirectoryDepth: z.number().min(0).max(99).default(3),
-	autostart: z.boolean()
+	autostart: z.boolean(),
+	dumpSource: z.string().min(1),
+	automaticDumpUpdates: z.boolean(),
+	dumpUpdateIntervalHours: z.number().min(1).max(720).default(24),
+	pauseDumpUpdatesOnMetered: z.boolean(),
+	dumpRowErrorPolicy: z.enum(['skip', 'abort']).default('skip'),
+	coverCdn: z.string().url(),
+	reviewDimensions: z
+		.array(z.string().trim().min(1).max(30))
+		.refine((dimensions) => new Set(dimensions).size === dimensions.length, {
+			message: 'Review dimensions must be unique'
+		}),
+	contentCategories: z.array(z.number()),
+	excludedPlatforms: z.array(z.string()),
+	includeVersions: z.boolean()
 });
 export type SettingsFormSchema = typeof settingsSchema;
 
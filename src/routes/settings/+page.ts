@@ -1,3 +1,4 @@
+import { canRollbackDumpImport } from '$lib/rust-bindings/dumps';
 import { getUserSettings } from '$lib/rust-bindings/helpers';
 import { settingsSchema } from '$lib/schemas';
 import { superValidate } from 'sveltekit-superforms';
@@ -5,7 +6,7 @@ import { zod } from 'sveltekit-superforms/adapters';
 
 export const load = async () => {
 	if (typeof window === 'undefined') {
-		return { form: superValidate(zod(settingsSchema)) };
+		return { form: superValidate(zod(settingsSchema)), canRollbackCatalogue: false };
 	}
 	const userSettings = await getUserSettings();
 	const formData = {
@@ -13,9 +14,20 @@ export const load = async () => {
 		executablePaths: userSettings.executable_paths ? userSettings.executable_paths.split(';') : [],
 		processMonitoringEnabled: userSettings.process_monitoring.enabled,
 		processMonitoringDirectoryDepth: userSettings.process_monitoring.directory_depth,
-		autostart: userSettings.autostart
+		autostart: userSettings.autostart,
+		dumpSource: userSettings.dump_source,
+		automaticDumpUpdates: userSettings.dump_updates.automatic,
+		dumpUpdateIntervalHours: userSettings.dump_updates.interval_hours,
+		pauseDumpUpdatesOnMetered: userSettings.dump_updates.pause_on_metered,
+		dumpRowErrorPolicy: userSettings.dump_updates.row_error_policy,
+		coverCdn: userSettings.cover_cdn,
+		reviewDimensions: userSettings.review_dimensions,
+		contentCategories: userSettings.content_filter.categories,
+		excludedPlatforms: userSettings.content_filter.excluded_platforms,
+		includeVersions: userSettings.content_filter.include_versions
 	};
 	const form = await superValidate(formData, zod(settingsSchema));
+	const canRollbackCatalogue = await canRollbackDumpImport();
 
-	return { form };
+	return { form, canRollbackCatalogue };
 };